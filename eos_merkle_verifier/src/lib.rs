@@ -0,0 +1,209 @@
+//! A `no_std` + `alloc` verifier for EOS action merkle proofs, for use in environments such as
+//! smart contracts or enclaves, where neither `std`, `serde_json` nor hex strings are available.
+//!
+//! Proofs are laid out exactly as the proof maker outputs them, ie the leaf, followed by the
+//! canonical siblings from the leaves up, followed by the root, only as raw 32 byte digests.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use bitcoin_hashes::{sha256, Hash};
+
+pub const DIGEST_LENGTH: usize = 32;
+
+pub type Digest = [u8; DIGEST_LENGTH];
+
+// NOTE: EOS marks a node as the left or right of a pair via the first bit of its first byte.
+// This & the pairing & branch folding below are the single source of that logic, & are shared
+// by the proof maker's merkle utils.
+
+pub fn set_first_bit_of_byte_to_zero(byte: u8) -> u8 {
+    // Left
+    byte & 0b0111_1111
+}
+
+pub fn set_first_bit_of_byte_to_one(byte: u8) -> u8 {
+    // Right
+    byte | 0b1000_0000
+}
+
+pub fn is_first_bit_of_byte_zero(byte: u8) -> bool {
+    byte & 0b1000_0000 == 0
+}
+
+pub fn make_canonical_left(mut digest: Digest) -> Digest {
+    digest[0] = set_first_bit_of_byte_to_zero(digest[0]);
+    digest
+}
+
+pub fn make_canonical_right(mut digest: Digest) -> Digest {
+    digest[0] = set_first_bit_of_byte_to_one(digest[0]);
+    digest
+}
+
+pub fn is_canonical_left(digest: &Digest) -> bool {
+    is_first_bit_of_byte_zero(digest[0])
+}
+
+pub fn is_canonical_right(digest: &Digest) -> bool {
+    !is_canonical_left(digest)
+}
+
+pub fn make_and_hash_canonical_pair(l: Digest, r: Digest) -> Digest {
+    let mut pair = [0u8; DIGEST_LENGTH * 2];
+    pair[..DIGEST_LENGTH].copy_from_slice(&make_canonical_left(l));
+    pair[DIGEST_LENGTH..].copy_from_slice(&make_canonical_right(r));
+    sha256::Hash::hash(&pair).into_inner()
+}
+
+pub fn get_merkle_root_from_branch(leaf: &Digest, branch: &[Digest]) -> Digest {
+    branch.iter().fold(*leaf, |node, sibling| {
+        if is_canonical_right(sibling) {
+            make_and_hash_canonical_pair(node, *sibling)
+        } else {
+            make_and_hash_canonical_pair(*sibling, node)
+        }
+    })
+}
+
+pub fn verify_merkle_branch(leaf: &Digest, branch: &[Digest], root: &Digest) -> bool {
+    &get_merkle_root_from_branch(leaf, branch) == root
+}
+
+pub fn verify_merkle_proof(merkle_proof: &[Digest], root: &Digest) -> bool {
+    // NOTE: The root at the end of a proof is NOT trusted, lest a tampered proof verify against itself.
+    match merkle_proof {
+        [leaf, branch @ .., proof_root] => {
+            proof_root == root && verify_merkle_branch(leaf, branch, root)
+        }
+        _ => false,
+    }
+}
+
+/// Splits concatenated bytes into digests, returning `None` if they're not a whole number of them.
+pub fn parse_digests(bytes: &[u8]) -> Option<Vec<Digest>> {
+    match bytes.len() % DIGEST_LENGTH {
+        0 => Some(
+            bytes
+                .chunks(DIGEST_LENGTH)
+                .map(|chunk| {
+                    let mut digest = [0u8; DIGEST_LENGTH];
+                    digest.copy_from_slice(chunk);
+                    digest
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn get_sample_digest_1() -> Digest {
+        // NOTE: 9b9babebfbdff48ce4002b5f3c7f999c0ee74707b6d121c47ef5db68c6be7262
+        [
+            0x9b, 0x9b, 0xab, 0xeb, 0xfb, 0xdf, 0xf4, 0x8c, 0xe4, 0x00, 0x2b, 0x5f, 0x3c, 0x7f,
+            0x99, 0x9c, 0x0e, 0xe7, 0x47, 0x07, 0xb6, 0xd1, 0x21, 0xc4, 0x7e, 0xf5, 0xdb, 0x68,
+            0xc6, 0xbe, 0x72, 0x62,
+        ]
+    }
+
+    fn get_sample_digest_2() -> Digest {
+        // NOTE: 122cd09d66ca7df007a35bd9c9be5484833f1a69ad0c8527c3e2a56b6955e761
+        [
+            0x12, 0x2c, 0xd0, 0x9d, 0x66, 0xca, 0x7d, 0xf0, 0x07, 0xa3, 0x5b, 0xd9, 0xc9, 0xbe,
+            0x54, 0x84, 0x83, 0x3f, 0x1a, 0x69, 0xad, 0x0c, 0x85, 0x27, 0xc3, 0xe2, 0xa5, 0x6b,
+            0x69, 0x55, 0xe7, 0x61,
+        ]
+    }
+
+    fn get_sample_digest_3() -> Digest {
+        [0x42; DIGEST_LENGTH]
+    }
+
+    #[test]
+    fn should_make_digests_canonical_left_and_right() {
+        let left = make_canonical_left(get_sample_digest_1());
+        let right = make_canonical_right(get_sample_digest_2());
+        assert_eq!(left[0], 0b0001_1011);
+        assert_eq!(right[0], 0b1001_0010);
+        assert_eq!(left[1..], get_sample_digest_1()[1..]);
+        assert_eq!(right[1..], get_sample_digest_2()[1..]);
+        assert!(is_canonical_left(&left) && !is_canonical_right(&left));
+        assert!(is_canonical_right(&right) && !is_canonical_left(&right));
+    }
+
+    #[test]
+    fn should_hash_canonical_pair() {
+        // NOTE: a26284468e89fe4a5cce763ca3b3d3d37d5fcb35f289c63f0558487ec57ace28
+        let expected_result = [
+            0xa2, 0x62, 0x84, 0x46, 0x8e, 0x89, 0xfe, 0x4a, 0x5c, 0xce, 0x76, 0x3c, 0xa3, 0xb3,
+            0xd3, 0xd3, 0x7d, 0x5f, 0xcb, 0x35, 0xf2, 0x89, 0xc6, 0x3f, 0x05, 0x58, 0x48, 0x7e,
+            0xc5, 0x7a, 0xce, 0x28,
+        ];
+        let result = make_and_hash_canonical_pair(get_sample_digest_1(), get_sample_digest_2());
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn should_verify_merkle_proof_of_each_leaf_of_odd_tree() {
+        // NOTE: The odd last leaf is paired with itself.
+        let (leaf_0, leaf_1, leaf_2) = (
+            get_sample_digest_1(),
+            get_sample_digest_2(),
+            get_sample_digest_3(),
+        );
+        let node_0 = make_and_hash_canonical_pair(leaf_0, leaf_1);
+        let node_1 = make_and_hash_canonical_pair(leaf_2, leaf_2);
+        let root = make_and_hash_canonical_pair(node_0, node_1);
+        let proofs = vec![
+            vec![
+                leaf_0,
+                make_canonical_right(leaf_1),
+                make_canonical_right(node_1),
+                root,
+            ],
+            vec![
+                leaf_1,
+                make_canonical_left(leaf_0),
+                make_canonical_right(node_1),
+                root,
+            ],
+            vec![
+                leaf_2,
+                make_canonical_right(leaf_2),
+                make_canonical_left(node_0),
+                root,
+            ],
+        ];
+        proofs
+            .iter()
+            .for_each(|proof| assert!(verify_merkle_proof(proof, &root)));
+    }
+
+    #[test]
+    fn should_not_verify_merkle_proof_against_wrong_root() {
+        let leaf = get_sample_digest_1();
+        let sibling = make_canonical_right(get_sample_digest_2());
+        let root = make_and_hash_canonical_pair(leaf, sibling);
+        let wrong_root = get_sample_digest_3();
+        assert!(!verify_merkle_proof(&[leaf, sibling, root], &wrong_root));
+        assert!(!verify_merkle_proof(
+            &[leaf, sibling, wrong_root],
+            &wrong_root
+        ));
+        assert!(!verify_merkle_proof(&[root], &root));
+    }
+
+    #[test]
+    fn should_parse_digests_from_concatenated_bytes() {
+        let bytes = [get_sample_digest_1(), get_sample_digest_2()].concat();
+        let result = parse_digests(&bytes).unwrap();
+        assert_eq!(result, vec![get_sample_digest_1(), get_sample_digest_2()]);
+        assert!(parse_digests(&bytes[1..]).is_none());
+    }
+}
@@ -0,0 +1,44 @@
+#ifndef EOS_ACTION_PROOF_MAKER_H
+#define EOS_ACTION_PROOF_MAKER_H
+
+/* Generated with cbindgen from `src/ffi.rs`. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define EOS_ACTION_PROOF_MAKER_SUCCESS 0
+
+#define EOS_ACTION_PROOF_MAKER_ERROR 1
+
+// Generates proof(s) exactly as the CLI's `generate` command does. On return, `*out` points to
+// the output JSON, or to the error message if the returned status is non-zero. Free it via
+// `eos_action_proof_maker_free_string`.
+//
+// # Safety
+//
+// `input_json` must be a valid nul-terminated string & `out` a valid pointer to write to.
+int eos_action_proof_maker_generate(const char *input_json, bool all, bool multi_proof, char **out);
+
+// Verifies a proof exactly as the CLI's `verify` command does when passed an `--action-mroot`.
+// On return, `*out` points to the verification JSON, or to the error message if the returned
+// status is non-zero. Free it via `eos_action_proof_maker_free_string`.
+//
+// # Safety
+//
+// `output_json` & `action_mroot_hex` must be valid nul-terminated strings & `out` a valid
+// pointer to write to.
+int eos_action_proof_maker_verify(const char *output_json,
+                                  const char *action_mroot_hex,
+                                  char **out);
+
+// Frees a string returned by this library. Passing null is a no-op.
+//
+// # Safety
+//
+// `string` must have been returned by this library & not already been freed.
+void eos_action_proof_maker_free_string(char *string);
+
+#endif /* EOS_ACTION_PROOF_MAKER_H */
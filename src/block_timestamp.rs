@@ -0,0 +1,184 @@
+use crate::{
+    error::AppError,
+    types::{BlockTimestamp, Result},
+};
+use std::{fmt, str::FromStr};
+
+// NOTE: EOS block timestamps count half-second slots since the start of the year 2000.
+const BLOCK_TIMESTAMP_EPOCH_MS: i64 = 946_684_800_000;
+const BLOCK_INTERVAL_MS: i64 = 500;
+const MS_PER_DAY: i64 = 86_400_000;
+
+fn get_days_since_unix_epoch(year: i64, month: i64, day: i64) -> i64 {
+    // NOTE: Howard Hinnant's `days_from_civil`, since `chrono` is unavailable in wasm builds. The
+    // tests cross-check it against `chrono` on other targets.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn get_date_from_days_since_unix_epoch(days: i64) -> (i64, i64, i64) {
+    // NOTE: Howard Hinnant's `civil_from_days`, the inverse of the above.
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn parse_timestamp_ms(timestamp: &str) -> Option<i64> {
+    let (date, time) = timestamp.trim_end_matches('Z').split_once('T')?;
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let date_parts = date
+        .split('-')
+        .map(|part| part.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    let time_parts = time
+        .split(':')
+        .map(|part| part.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    // NOTE: Sub-millisecond digits would be silently truncated, so they're rejected instead.
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let millis = format!("{:0<3}", fraction).parse::<i64>().ok()?;
+    match (date_parts.as_slice(), time_parts.as_slice()) {
+        (
+            [year, month @ 1..=12, day @ 1..=31],
+            [hours @ 0..=23, minutes @ 0..=59, seconds @ 0..=59],
+        ) => {
+            let days = get_days_since_unix_epoch(*year, *month, *day);
+            // NOTE: Days past the end of the month (eg `2020-02-31`) would roll into the next.
+            if get_date_from_days_since_unix_epoch(days) != (*year, *month, *day) {
+                return None;
+            }
+            Some((((days * 24 + hours) * 60 + minutes) * 60 + seconds) * 1000 + millis)
+        }
+        _ => None,
+    }
+}
+
+impl FromStr for BlockTimestamp {
+    type Err = AppError;
+
+    fn from_str(timestamp: &str) -> Result<Self> {
+        parse_timestamp_ms(timestamp)
+            .map(|ms| ms - BLOCK_TIMESTAMP_EPOCH_MS)
+            .filter(|ms| ms % BLOCK_INTERVAL_MS == 0)
+            .and_then(|ms| u32::try_from(ms / BLOCK_INTERVAL_MS).ok())
+            .map(BlockTimestamp)
+            .ok_or_else(|| AppError::Custom(format!("✘ Invalid block timestamp: {}", timestamp)))
+    }
+}
+
+impl fmt::Display for BlockTimestamp {
+    /// Formats the timestamp as `nodeos` does, eg `2020-04-06T16:19:29.500`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ms = BLOCK_TIMESTAMP_EPOCH_MS + i64::from(self.0) * BLOCK_INTERVAL_MS;
+        let (year, month, day) = get_date_from_days_since_unix_epoch(ms / MS_PER_DAY);
+        let ms_of_day = ms % MS_PER_DAY;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+            year,
+            month,
+            day,
+            ms_of_day / 3_600_000,
+            ms_of_day / 60_000 % 60,
+            ms_of_day / 1000 % 60,
+            ms_of_day % 1000
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_block_timestamps() {
+        let parse = |timestamp: &str| BlockTimestamp::from_str(timestamp).unwrap().0;
+        assert_eq!(parse("2000-01-01T00:00:00.000"), 0);
+        assert_eq!(parse("2000-01-01T00:00:00.500Z"), 1);
+        assert_eq!(parse("2020-04-06T16:19:29.500"), 1_279_010_339);
+        assert!(BlockTimestamp::from_str("2020-04-06T16:19:29.250").is_err());
+        assert!(BlockTimestamp::from_str("1999-12-31T23:59:59.500").is_err());
+        assert!(BlockTimestamp::from_str("2020-13-06T16:19:29").is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn should_agree_with_chrono() {
+        use chrono::{Duration, NaiveDate};
+        let epoch = NaiveDate::from_ymd_opt(2000, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .unwrap();
+        (0..u32::MAX).step_by(9_999_991).for_each(|slot| {
+            let date_time = epoch + Duration::milliseconds(i64::from(slot) * BLOCK_INTERVAL_MS);
+            let timestamp = date_time.format("%Y-%m-%dT%H:%M:%S%.3f").to_string();
+            assert_eq!(BlockTimestamp(slot).to_string(), timestamp);
+            assert_eq!(BlockTimestamp::from_str(&timestamp).unwrap().0, slot);
+        });
+        // NOTE: Slots run out in 2068, hence the range of years.
+        (2000..2068).for_each(|year| {
+            (1..=12).for_each(|month| {
+                (28..=31).for_each(|day| {
+                    let timestamp = format!("{:04}-{:02}-{:02}T00:00:00.000", year, month, day);
+                    assert_eq!(
+                        BlockTimestamp::from_str(&timestamp).is_ok(),
+                        NaiveDate::from_ymd_opt(year, month, day).is_some()
+                    );
+                })
+            })
+        });
+    }
+
+    #[test]
+    fn should_fail_to_parse_impossible_dates() {
+        assert!(BlockTimestamp::from_str("2020-02-29T00:00:00.000").is_ok());
+        assert!(BlockTimestamp::from_str("2021-02-29T00:00:00.000").is_err());
+        assert!(BlockTimestamp::from_str("2020-02-31T00:00:00.000").is_err());
+        assert!(BlockTimestamp::from_str("2020-04-31T00:00:00.000").is_err());
+    }
+
+    #[test]
+    fn should_fail_to_parse_sub_millisecond_digits_rather_than_truncate_them() {
+        assert_eq!(
+            BlockTimestamp::from_str("2020-04-06T16:19:29.5").unwrap().0,
+            1_279_010_339
+        );
+        assert!(BlockTimestamp::from_str("2020-04-06T16:19:29.5001").is_err());
+        assert!(BlockTimestamp::from_str("2020-04-06T16:19:29.500000").is_err());
+        assert!(BlockTimestamp::from_str("2020-04-06T16:19:29.").is_err());
+        assert!(BlockTimestamp::from_str("2020-04-06T16:19:29.-50").is_err());
+    }
+
+    #[test]
+    fn should_format_block_timestamps_as_nodeos_does() {
+        let timestamps = [
+            "2000-01-01T00:00:00.000",
+            "2020-02-29T23:59:59.500",
+            "2020-04-06T16:19:29.500",
+            "2022-09-21T13:48:06.000",
+        ];
+        timestamps.iter().for_each(|timestamp| {
+            assert_eq!(
+                BlockTimestamp::from_str(timestamp).unwrap().to_string(),
+                *timestamp
+            )
+        });
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    eos_merkle_utils::get_merkle_tree,
+    state::State,
+    types::{Bytes, EosActionReceipts, Result},
+};
+use eos_chain::Digest;
+
+pub fn get_action_receipt_digests(action_receipts: &EosActionReceipts) -> Result<Vec<Bytes>> {
+    action_receipts
+        .iter()
+        .map(|receipt| Ok(receipt.digest()?.as_bytes().to_vec()))
+        .collect()
+}
+
+pub fn build_merkle_tree_and_put_in_state(state: State) -> Result<State> {
+    info!("✔ Building merkle tree from action receipts...");
+    state
+        .get_eos_action_receipts()
+        .and_then(get_action_receipt_digests)
+        .and_then(get_merkle_tree)
+        .and_then(|merkle_tree| state.add_merkle_tree(merkle_tree))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eos_merkle_utils::get_merkle_root_from_tree, test_utils::get_sample_action_receipts_n,
+    };
+
+    #[test]
+    fn should_build_merkle_tree_from_action_receipts() {
+        let action_receipts = get_sample_action_receipts_n(2).unwrap();
+        let leaves = get_action_receipt_digests(&action_receipts).unwrap();
+        let tree = get_merkle_tree(leaves.clone()).unwrap();
+        assert_eq!(tree[0].concat(), leaves.concat());
+        assert_eq!(tree[tree.len() - 1].len(), 1);
+        tree.iter()
+            .skip(1)
+            .enumerate()
+            .for_each(|(i, level)| assert_eq!(level.len(), tree[i].len().div_ceil(2)));
+        assert_eq!(
+            hex::encode(get_merkle_root_from_tree(&tree)),
+            "ee19c89344a2fd3faa2886d95bf23035e8af484f95d215674b96a3e0dbd157f8"
+        );
+    }
+}
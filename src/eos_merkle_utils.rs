@@ -1,519 +1,587 @@
 use crate::error::AppError;
-use crate::types::{Byte, Bytes, MerkleProof, Result};
-use bitcoin_hashes::{sha256, Hash};
-
-pub type CanonicalLeft = Bytes;
-pub type CanonicalRight = Bytes;
-pub type Sha256Hash = bitcoin_hashes::sha256::Hash;
-pub type CanonicalPair = (CanonicalLeft, CanonicalRight);
-
-fn set_first_bit_of_byte_to_zero(mut byte: Byte) -> Byte {
-    // Left
-    byte &= 0b0111_1111;
-    byte
+use crate::hex_utils::{convert_bytes_to_checksum256, decode_checksum256};
+use crate::types::{Byte, Bytes, Checksum256Bytes, MerkleMultiProof, MerkleProof, Result};
+pub use eos_merkle_verifier::{
+    get_merkle_root_from_branch, make_and_hash_canonical_pair, make_canonical_left,
+    make_canonical_right, verify_merkle_branch,
+};
+
+pub type MerkleLeaf = Checksum256Bytes;
+pub type MerkleRoot = Checksum256Bytes;
+pub type MerkleBranch = Vec<Checksum256Bytes>;
+pub type MerkleTreeLevel = Vec<Checksum256Bytes>;
+pub type MerkleTree = Vec<MerkleTreeLevel>;
+
+fn convert_bytes_to_merkle_leaves(leaves: &[Bytes]) -> Result<Vec<MerkleLeaf>> {
+    leaves
+        .iter()
+        .map(|leaf| convert_bytes_to_checksum256(leaf))
+        .collect()
 }
 
-fn set_first_bit_of_byte_to_one(mut byte: Byte) -> Byte {
-    // Right
-    byte |= 0b1000_0000;
-    byte
+pub fn get_merkle_tree(leaves: Vec<Bytes>) -> Result<MerkleTree> {
+    // NOTE: Levels are stored _before_ any odd last node is duplicated to pair with itself.
+    let mut tree = vec![convert_bytes_to_merkle_leaves(&leaves)?];
+    while tree[tree.len() - 1].len() > 1 {
+        let next_level = tree[tree.len() - 1]
+            .chunks(2)
+            .map(|pair| make_and_hash_canonical_pair(pair[0], pair[pair.len() - 1]))
+            .collect();
+        tree.push(next_level);
+    }
+    Ok(tree)
 }
 
-fn set_first_bit_of_hash_to_one(mut hash: Bytes) -> Bytes {
-    hash[0] = set_first_bit_of_byte_to_one(hash[0]);
-    hash
-}
-
-fn set_first_bit_of_hash_to_zero(mut hash: Bytes) -> Bytes {
-    hash[0] = set_first_bit_of_byte_to_zero(hash[0]);
-    hash
-}
-
-fn make_canonical_left(hash: Bytes) -> CanonicalLeft {
-    set_first_bit_of_hash_to_zero(hash)
-}
-
-fn make_canonical_right(hash: Bytes) -> CanonicalRight {
-    set_first_bit_of_hash_to_one(hash)
-}
-
-fn is_canonical_left(hash: &Bytes) -> bool {
-    hash[0] & 0b1000_0000 == 0
+pub fn get_merkle_root_from_tree(tree: &MerkleTree) -> MerkleRoot {
+    // NOTE: As in `nodeos`, the root of an empty tree is the empty digest.
+    match tree[tree.len() - 1].first() {
+        Some(root) => *root,
+        None => [0; 32],
+    }
 }
 
-fn is_canonical_right(hash: &Bytes) -> bool {
-    !is_canonical_left(hash)
+pub fn generate_merkle_proof(mut index: usize, tree: &MerkleTree) -> Result<MerkleProof> {
+    match index < tree[0].len() {
+        false => Err(AppError::Custom(format!(
+            "✘ Error generating merkle proof!\n{}",
+            "✘ Index out of bounds!"
+        ))),
+        true => {
+            let mut proof = vec![hex::encode(tree[0][index])];
+            for level in tree.iter().take(tree.len() - 1) {
+                if index % 2 == 1 {
+                    proof.push(hex::encode(make_canonical_left(level[index - 1])))
+                } else {
+                    let sibling_index = if index + 1 < level.len() {
+                        index + 1
+                    } else {
+                        index
+                    };
+                    proof.push(hex::encode(make_canonical_right(level[sibling_index])))
+                }
+                index /= 2;
+            }
+            proof.push(hex::encode(get_merkle_root_from_tree(tree)));
+            Ok(proof)
+        }
+    }
 }
 
-fn make_canonical_pair(l: Bytes, r: Bytes) -> CanonicalPair {
-    (make_canonical_left(l), make_canonical_right(r))
+fn decode_merkle_proof(merkle_proof: &MerkleProof) -> Result<Vec<Checksum256Bytes>> {
+    if merkle_proof.len() < 2 {
+        return Err(AppError::Custom(
+            "✘ A merkle proof must contain at least a leaf & a root!".to_string(),
+        ));
+    }
+    // NOTE: Proofs may be untrusted, so every node must be a whole 32 byte digest.
+    merkle_proof
+        .iter()
+        .map(|node| decode_checksum256(node))
+        .collect()
 }
 
-fn concatenate_canonical_pair(mut pair: CanonicalPair) -> Bytes {
-    pair.0.append(&mut pair.1);
-    pair.0
+pub fn split_merkle_proof(
+    merkle_proof: &MerkleProof,
+) -> Result<(MerkleLeaf, MerkleBranch, MerkleRoot)> {
+    let mut nodes = decode_merkle_proof(merkle_proof)?;
+    let root = nodes.remove(nodes.len() - 1);
+    let leaf = nodes.remove(0);
+    Ok((leaf, nodes, root))
 }
 
-fn hash_canonical_pair(pair: CanonicalPair) -> Sha256Hash {
-    sha256::Hash::hash(&concatenate_canonical_pair(pair))
+pub fn verify_merkle_proof(merkle_proof: &MerkleProof, root: &[Byte]) -> Result<bool> {
+    let root = convert_bytes_to_checksum256(root)?;
+    decode_merkle_proof(merkle_proof)
+        .map(|nodes| eos_merkle_verifier::verify_merkle_proof(&nodes, &root))
 }
 
-fn make_and_hash_canonical_pair(l: Bytes, r: Bytes) -> Bytes {
-    hash_canonical_pair(make_canonical_pair(l, r)).to_vec()
+fn get_index_out_of_bounds_err(index: usize, num_leaves: usize) -> AppError {
+    AppError::Custom(format!(
+        "✘ Leaf index {} is out of bounds for a tree with {} leaves!",
+        index, num_leaves
+    ))
 }
 
-pub fn get_merkle_digest(mut leaves: Vec<Bytes>) -> Bytes {
-    if leaves.is_empty() {
-        return vec![0x00]; // TODO Need a type for this!
-    }
-    while leaves.len() > 1 {
-        if leaves.len() % 2 != 0 {
-            let last = leaves[leaves.len() - 1].clone();
-            leaves.push(last);
-        }
-        for i in 0..(leaves.len() / 2) {
-            leaves[i] = hash_canonical_pair(make_canonical_pair(
-                leaves[2 * i].clone(),
-                leaves[(2 * i) + 1].clone(),
-            ))
-            .to_vec();
-        }
-        leaves.resize(leaves.len() / 2, vec![0x00]);
-    }
-    leaves[0].clone()
-}
-
-pub fn generate_merkle_proof(mut index: usize, mut leaves: Vec<Bytes>) -> Result<MerkleProof> {
-    let mut proof = Vec::new();
-    proof.push(hex::encode(leaves[index].clone()));
-    match index < leaves.len() {
-        false => Err(AppError::Custom(format!(
-            "✘ Error generating merkle proof!\n{}",
-            "✘ Index out of bounds!"
-        ))),
-        true => {
-            while leaves.len() > 1 {
-                if leaves.len() % 2 != 0 {
-                    let last = leaves[leaves.len() - 1].clone();
-                    leaves.push(last)
-                }
-                for i in 0..leaves.len() / 2 {
-                    if index / 2 == i {
-                        if index % 2 != 0 {
-                            proof.push(hex::encode(make_canonical_left(leaves[2 * i].clone())))
-                        } else {
-                            proof.push(hex::encode(make_canonical_right(leaves[2 * i + 1].clone())))
-                        }
-                        index /= 2;
+pub fn generate_merkle_multi_proof(
+    indices: &[usize],
+    tree: &MerkleTree,
+) -> Result<MerkleMultiProof> {
+    let num_leaves = tree[0].len();
+    let mut leaf_indices = indices.to_vec();
+    leaf_indices.sort_unstable();
+    leaf_indices.dedup();
+    match leaf_indices.last() {
+        None => Err(AppError::Custom(
+            "✘ Cannot generate a multi-proof for zero leaves!".to_string(),
+        )),
+        Some(index) if *index >= num_leaves => Err(get_index_out_of_bounds_err(*index, num_leaves)),
+        Some(_) => {
+            // NOTE: Per level, only those siblings which cannot be computed from the known nodes are
+            // included, in ascending index order. An odd last node pairs with itself so needs none.
+            let mut nodes = Vec::new();
+            let mut known_indices = leaf_indices.clone();
+            for level in tree.iter().take(tree.len() - 1) {
+                let mut parent_indices: Vec<usize> = Vec::new();
+                for index in known_indices.iter() {
+                    let sibling_index = index ^ 1;
+                    if sibling_index < level.len()
+                        && known_indices.binary_search(&sibling_index).is_err()
+                    {
+                        nodes.push(hex::encode(level[sibling_index]));
+                    }
+                    if parent_indices.last() != Some(&(index / 2)) {
+                        parent_indices.push(index / 2);
                     }
-                    leaves[i] = hash_canonical_pair(make_canonical_pair(
-                        leaves[2 * i].clone(),
-                        leaves[2 * i + 1].clone(),
-                    ))
-                    .to_vec()
                 }
-                leaves.resize(leaves.len() / 2, vec![0x00]);
+                known_indices = parent_indices;
             }
-            proof.push(hex::encode(leaves[0].clone()));
-            Ok(proof)
+            Ok(MerkleMultiProof {
+                num_leaves,
+                leaf_indices,
+                nodes,
+            })
         }
     }
 }
 
-pub fn verify_merkle_proof(merkle_proof: &MerkleProof) -> Result<bool> {
-    let mut leaves = Vec::new();
-    for proof in merkle_proof.iter() {
-        leaves.push(hex::decode(proof.clone())?)
-    }
-    let mut node = leaves[0].clone();
-    for leaf in leaves.iter().take(leaves.len() - 1).skip(1) {
-        if is_canonical_right(leaf) {
-            node = make_and_hash_canonical_pair(node, leaf.clone());
-        } else {
-            node = make_and_hash_canonical_pair(leaf.clone(), node);
+pub fn get_merkle_root_from_multi_proof(
+    leaves: &[MerkleLeaf],
+    multi_proof: &MerkleMultiProof,
+) -> Result<MerkleRoot> {
+    if leaves.is_empty() || leaves.len() != multi_proof.leaf_indices.len() {
+        return Err(AppError::Custom(
+            "✘ Number of leaves does NOT match number of multi-proof leaf indices!".to_string(),
+        ));
+    }
+    if multi_proof
+        .leaf_indices
+        .windows(2)
+        .any(|pair| pair[0] >= pair[1])
+    {
+        return Err(AppError::Custom(
+            "✘ Multi-proof leaf indices must be strictly ascending!".to_string(),
+        ));
+    }
+    if let Some(index) = multi_proof
+        .leaf_indices
+        .iter()
+        .find(|index| **index >= multi_proof.num_leaves)
+    {
+        return Err(get_index_out_of_bounds_err(*index, multi_proof.num_leaves));
+    }
+    let mut nodes = multi_proof
+        .nodes
+        .iter()
+        .map(|node| decode_checksum256(node))
+        .collect::<Result<Vec<Checksum256Bytes>>>()?
+        .into_iter();
+    let mut get_next_node = || {
+        nodes
+            .next()
+            .ok_or_else(|| AppError::Custom("✘ Multi-proof has too few nodes!".to_string()))
+    };
+    let mut known_nodes: Vec<(usize, Checksum256Bytes)> = multi_proof
+        .leaf_indices
+        .iter()
+        .cloned()
+        .zip(leaves.iter().cloned())
+        .collect();
+    let mut level_len = multi_proof.num_leaves;
+    while level_len > 1 {
+        let mut parent_nodes = Vec::new();
+        let mut i = 0;
+        while i < known_nodes.len() {
+            let (index, node) = known_nodes[i];
+            let mut num_consumed = 1;
+            let (left, right) = if index % 2 == 1 {
+                (get_next_node()?, node)
+            } else if index + 1 == level_len {
+                (node, node)
+            } else if i + 1 < known_nodes.len() && known_nodes[i + 1].0 == index + 1 {
+                num_consumed = 2;
+                (node, known_nodes[i + 1].1)
+            } else {
+                (node, get_next_node()?)
+            };
+            parent_nodes.push((index / 2, make_and_hash_canonical_pair(left, right)));
+            i += num_consumed;
         }
+        known_nodes = parent_nodes;
+        level_len = level_len / 2 + level_len % 2;
+    }
+    match nodes.next() {
+        Some(_) => Err(AppError::Custom(
+            "✘ Multi-proof has too many nodes!".to_string(),
+        )),
+        None => Ok(known_nodes.remove(0).1),
     }
-    Ok(Some(&node) == leaves.last())
 }
 
-/* FIXME Reinstate!
+pub fn verify_merkle_multi_proof(
+    leaves: &[MerkleLeaf],
+    multi_proof: &MerkleMultiProof,
+    root: &[Byte],
+) -> Result<bool> {
+    get_merkle_root_from_multi_proof(leaves, multi_proof)
+        .map(|computed_root| computed_root[..] == *root)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use eos_chain::{
-        AccountName, Action, ActionName, ActionReceipt, utils::flat_map::FlatMap, PermissionLevel,
-        PermissionName, SerializeData,
+    use crate::{
+        build_merkle_tree::get_action_receipt_digests,
+        parse_eos_action_receipts::parse_eos_action_receipt_json,
+        test_utils::{get_sample_action_receipts_n, get_sample_eos_block_n},
+        types::{AuthSequenceJson, EosActionReceiptJson},
     };
-    use hex;
-    use std::str::FromStr;
-
-    fn get_expected_digest_1() -> &'static str {
-        "9b9babebfbdff48ce4002b5f3c7f999c0ee74707b6d121c47ef5db68c6be7262"
-    }
-
-    fn get_expected_digest_2() -> &'static str {
-        "122cd09d66ca7df007a35bd9c9be5484833f1a69ad0c8527c3e2a56b6955e761"
-    }
-
-    fn get_expected_digest_bytes_1() -> Bytes {
-        hex::decode(get_expected_digest_1()).unwrap()
-    }
-
-    fn get_expected_digest_bytes_2() -> Bytes {
-        hex::decode(get_expected_digest_2()).unwrap()
-    }
-
-    fn get_expected_first_byte_1() -> Byte {
-        0b0001_1011
-    }
-
-    fn get_expected_first_byte_2() -> Byte {
-        0b1001_0010
-    }
-
-    fn get_sample_canonical_pair() -> CanonicalPair {
-        make_canonical_pair(get_expected_digest_bytes_1(), get_expected_digest_bytes_2())
+    use eos_chain::Digest;
+
+    fn get_action_receipt_digest(
+        receiver: &str,
+        act_digest: &str,
+        global_sequence: u64,
+        recv_sequence: u64,
+        auth_sequence: &[(&str, u64)],
+        code_sequence: usize,
+        abi_sequence: usize,
+    ) -> Bytes {
+        parse_eos_action_receipt_json(&EosActionReceiptJson {
+            tx_id: "".to_string(),
+            receiver: receiver.to_string(),
+            act_digest: act_digest.to_string(),
+            global_sequence,
+            recv_sequence,
+            auth_sequence: auth_sequence
+                .iter()
+                .map(|(account, sequence)| AuthSequenceJson(account.to_string(), *sequence))
+                .collect(),
+            code_sequence,
+            abi_sequence,
+        })
+        .unwrap()
+        .digest()
+        .unwrap()
+        .as_bytes()
+        .to_vec()
     }
 
-    fn get_sample_action_receipts() -> Vec<ActionReceipt> {
+    fn get_sample_action_digests() -> Vec<Bytes> {
         vec![
-            ActionReceipt::new(
+            get_action_receipt_digest(
                 "eosio",
                 "3b434aa9331f5e2a0e7a0060d576fa6688406667100bdf3458104dede44ec4e9",
-                62826453,
-                12,
                 503081363,
+                62826453,
+                &[("eosio", 61285932)],
                 10,
-                vec![FlatMap::new(AccountName::from_str("eosio").unwrap(), 61285932)],
-            )
-            .unwrap(),
-            ActionReceipt::new(
+                12,
+            ),
+            get_action_receipt_digest(
                 "pokerpokerts",
                 "3d380413463e8716ef9c1f8c853dfab0c70f209cce75cae9a5b74e4e678a68a0",
-                241512,
-                4,
                 503081364,
+                241512,
+                &[("pokerpokerts", 241552)],
                 30,
-                vec![FlatMap::new(AccountName::from_str("pokerpokerts").unwrap(), 241552)],
-            )
-            .unwrap(),
-            ActionReceipt::new(
+                4,
+            ),
+            get_action_receipt_digest(
                 "oracleoracle",
                 "065527f0429dfa9bb79575ec5270b20f714fb9e61a9ce6ba9c86b2e69a773f82",
+                503081365,
                 531231,
+                &[("feeder111112", 152730)],
                 2,
-                503081365,
                 2,
-                vec![FlatMap::new(AccountName::from_str("feeder111112").unwrap(), 152730)],
-            )
-            .unwrap(),
-            ActionReceipt::new(
+            ),
+            get_action_receipt_digest(
                 "dvmh1tbb1him",
                 "18e42aa86473509cf620764ca606136b037e1a8ee6fb8efaa8fa657c7fa2fffc",
-                805647,
-                2,
                 503081366,
+                805647,
+                &[("dvmh1tbb1him", 805667)],
                 1,
-                vec![FlatMap::new(AccountName::from_str("dvmh1tbb1him").unwrap(), 805667)],
-            )
-            .unwrap(),
+                2,
+            ),
         ]
     }
 
-    fn get_sample_action_digests() -> Vec<Bytes> {
-        get_sample_action_receipts()
-            .into_iter()
-            .map(|receipt| receipt.to_digest())
-            .collect()
+    fn get_sample_merkle_tree_n(n: usize) -> MerkleTree {
+        get_merkle_tree(
+            get_action_receipt_digests(&get_sample_action_receipts_n(n).unwrap()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn get_leaves(tree: &MerkleTree, indices: &[usize]) -> Vec<MerkleLeaf> {
+        indices.iter().map(|i| tree[0][*i]).collect()
     }
 
     #[test]
-    fn should_set_first_bit_of_byte_to_zero() {
-        let byte = 0b1011_1011;
-        let expected_result = 0b0011_1011;
-        let result = set_first_bit_of_byte_to_zero(byte);
-        assert!(result == expected_result);
+    fn should_get_correct_action_receipt_digest() {
+        let expected_result = "6cd473b189a292bd520cac3430cc7934273da81cc3417376194a5d757b4abdc8";
+        let result = get_action_receipt_digest(
+            "eosio",
+            "a6a370c6569034a4cc41935dd88f83d1c64e0414580872f29d87f69fe7a5d769",
+            498637597,
+            60725518,
+            &[("eosio", 59191700)],
+            10,
+            12,
+        );
+        assert_eq!(hex::encode(result), expected_result);
     }
 
     #[test]
-    fn should_set_first_bit_of_byte_to_one() {
-        let byte = 0b0011_0011;
-        let expected_result = 0b1011_0011;
-        let result = set_first_bit_of_byte_to_one(byte);
-        assert!(result == expected_result);
+    fn should_get_merkle_root_for_an_even_number_of_action_receipts() {
+        // NOTE: Test vector = https://jungle.bloks.io/block/58316764
+        let expected_result = "2f013d3ed57c89f1824772d18a4a74c043574bad47e9c6f088136e7595511810";
+        let digests = vec![
+            get_action_receipt_digest(
+                "eosio",
+                "8e3e721a497dbae5e5fde0bb43e9086628809efaf102b763a3e9820adce9ce8f",
+                503056735,
+                62815613,
+                &[("eosio", 61275209)],
+                10,
+                12,
+            ),
+            get_action_receipt_digest(
+                "provabletokn",
+                "4b991cebb3e6667b242aca3fb011623cd8ce2be2e8c24958da551c7b3ba68903",
+                503056736,
+                2884,
+                &[("provabletokn", 3090)],
+                80,
+                48,
+            ),
+        ];
+        let result = get_merkle_root_from_tree(&get_merkle_tree(digests).unwrap());
+        assert_eq!(hex::encode(result), expected_result);
     }
 
     #[test]
-    fn should_set_first_bit_of_hash_to_one() {
-        let hash = get_expected_digest_bytes_2();
-        let result = set_first_bit_of_hash_to_one(hash.clone());
-        for i in 0..hash.len() {
-            if i == 0 {
-                assert!(result[i] == get_expected_first_byte_2());
-            } else {
-                assert!(result[i] == hash[i]);
-            }
-        }
+    fn should_get_merkle_root_for_an_odd_number_of_action_receipts_gt_one() {
+        // NOTE: Test vector = https://jungle.bloks.io/block/58319528
+        let expected_result = "593f54cbc0b877b30cec5e510838b2b16ca00aca43e21d204d21eb8e8f947aa0";
+        let digests = vec![
+            get_action_receipt_digest(
+                "eosio",
+                "23ab74b930cceea6061e1c4580ec988bf483a77e225cfca254d832928b4d1b36",
+                503062766,
+                62818486,
+                &[("eosio", 61277973)],
+                10,
+                12,
+            ),
+            get_action_receipt_digest(
+                "eosebetbullj",
+                "b9243d8513e25705e89d7ccd0491f4a57d07b9866fd89d3446887af852cfed15",
+                503062767,
+                1440226,
+                &[("eosebetbullj", 1440687)],
+                215,
+                215,
+            ),
+            get_action_receipt_digest(
+                "dvmh1tbb1him",
+                "4bd1d3e987cd13e3d108a9a0cd185bf022cb1a826f69f163fcd109db54ba799f",
+                503062768,
+                804629,
+                &[("dvmh1tbb1him", 804649)],
+                1,
+                2,
+            ),
+        ];
+        let result = get_merkle_root_from_tree(&get_merkle_tree(digests).unwrap());
+        assert_eq!(hex::encode(result), expected_result);
     }
 
     #[test]
-    fn should_set_first_bit_of_hash_to_zero() {
-        let hash = get_expected_digest_bytes_1();
-        let result = set_first_bit_of_hash_to_zero(hash.clone());
-        for i in 0..hash.len() {
-            if i == 0 {
-                assert!(result[i] == get_expected_first_byte_1());
-            } else {
-                assert!(result[i] == hash[i]);
-            }
-        }
+    fn should_get_action_mroot_when_action_has_gt_one_auth_sequence() {
+        // NOTE: Test vector = https://jungle.bloks.io/block/58345436
+        let expected_result = "f93a91688d12170c24807d4bd507cf52dcde962ae4a41a86fe55231dee4df348";
+        let digests = vec![
+            get_action_receipt_digest(
+                "eosio",
+                "2d5371b958af052629f3fb62ede1bfcd94703675bc734535bf87fb615284dba3",
+                503124645,
+                62844592,
+                &[("eosio", 61303891)],
+                10,
+                12,
+            ),
+            get_action_receipt_digest(
+                "authsequence",
+                "ae341469a7b3936c70e9684a42ef8fc1975f1bb2fe1f3b0b1105eda7d3a6276a",
+                503124646,
+                10,
+                &[("othrsequence", 14), ("rick11111111", 268)],
+                1,
+                1,
+            ),
+        ];
+        let result = get_merkle_root_from_tree(&get_merkle_tree(digests).unwrap());
+        assert_eq!(hex::encode(result), expected_result);
     }
 
     #[test]
-    fn should_make_hash_canonical_right() {
-        let hash = get_expected_digest_bytes_2();
-        let result = make_canonical_right(hash.clone());
-        for i in 0..hash.len() {
-            if i == 0 {
-                assert!(result[i] == get_expected_first_byte_2());
-            } else {
-                assert!(result[i] == hash[i]);
-            }
-        }
+    fn should_get_action_mroot_for_four_actions_correctly() {
+        let expected_result = "8b4e5e5d3e7587065896d0076d65c72e03c11a9159d414eb3a2363b59108116a";
+        let result =
+            get_merkle_root_from_tree(&get_merkle_tree(get_sample_action_digests()).unwrap());
+        assert_eq!(hex::encode(result), expected_result);
     }
 
     #[test]
-    fn should_make_hash_canonical_left() {
-        let hash = get_expected_digest_bytes_1();
-        let result = make_canonical_left(hash.clone());
-        for i in 0..hash.len() {
-            if i == 0 {
-                assert!(result[i] == get_expected_first_byte_1());
-            } else {
-                assert!(result[i] == hash[i]);
-            }
-        }
+    fn should_fail_to_get_merkle_tree_with_short_leaf() {
+        let mut digests = get_sample_action_digests();
+        digests[2].pop();
+        assert!(get_merkle_tree(digests).is_err());
     }
 
     #[test]
-    fn canonical_left_hash_should_be_canonical_left() {
-        let hash = get_expected_digest_bytes_1();
-        let canonical_left_hash = make_canonical_left(hash.clone());
-        let is_left = is_canonical_left(&canonical_left_hash);
-        let is_right = is_canonical_right(&canonical_left_hash);
-        assert!(is_left);
-        assert!(!is_right);
+    fn should_generate_merkle_proof_correctly() {
+        let expected_result = vec![
+            "41a91de4e161bc10ff6f7173822cf3f6417dd9bfc3dc88cf8bdd8196322837ce",
+            "d14a4b2157aaaa4fec077069a8617c53b4e3f75c142a6aea5aa24bd031578e96",
+            "3d8ddd7684e1f38a0f3c6880d179fc43ae730f9363eafe842cc85484450e2613",
+            "8b4e5e5d3e7587065896d0076d65c72e03c11a9159d414eb3a2363b59108116a",
+        ];
+        let result =
+            generate_merkle_proof(2, &get_merkle_tree(get_sample_action_digests()).unwrap())
+                .unwrap();
+        assert_eq!(result, expected_result);
     }
 
     #[test]
-    fn canonical_right_hash_should_be_canonical_right() {
-        let hash = get_expected_digest_bytes_2();
-        let canonical_right_hash = make_canonical_right(hash.clone());
-        let is_left = is_canonical_left(&canonical_right_hash);
-        let is_right = is_canonical_right(&canonical_right_hash);
-        assert!(!is_left);
-        assert!(is_right);
+    fn should_fail_to_generate_merkle_proof_for_out_of_bounds_index() {
+        let tree = get_merkle_tree(get_sample_action_digests()).unwrap();
+        assert!(generate_merkle_proof(4, &tree).is_err());
     }
 
     #[test]
-    fn should_get_correct_action_digest() {
-        let account_name = AccountName::from_str("provabletokn").unwrap();
-        let action_name = ActionName::from_str("event").unwrap();
-        let actor = AccountName::from_str("provabletokn").unwrap();
-        let permission = PermissionName::from_str("active").unwrap();
-        let permission_level = PermissionLevel { actor, permission };
-        let authorization = vec![permission_level];
-        let data = hex::decode(
-            "e0d2b86b1a3962343021cd2a1eb3e9ad672b00000000000004454f53000000002a3078303236644336413433353631444138413641373735353338623139324133653933366330463239422301000000000000"
-            ).unwrap();
-        let action = Action::new(account_name, action_name, authorization, data);
-        let serialized_action = &action.to_serialize_data();
-        let result = sha256::Hash::hash(serialized_action).to_string();
-        assert!(result == get_expected_digest_1().to_string());
+    fn should_split_merkle_proof() {
+        let proof =
+            generate_merkle_proof(2, &get_merkle_tree(get_sample_action_digests()).unwrap())
+                .unwrap();
+        let (leaf, branch, root) = split_merkle_proof(&proof).unwrap();
+        assert_eq!(hex::encode(leaf), proof[0]);
+        assert_eq!(branch.len(), 2);
+        assert_eq!(hex::encode(root), proof[3]);
+        assert_eq!(get_merkle_root_from_branch(&leaf, &branch), root);
     }
 
     #[test]
-    fn should_make_canonical_pair() {
-        let digest_1 = get_expected_digest_bytes_1();
-        let digest_2 = get_expected_digest_bytes_2();
-        let result = make_canonical_pair(digest_1.clone(), digest_2.clone());
-        for i in 0..result.0.len() {
-            if i == 0 {
-                assert!(result.0[i] == get_expected_first_byte_1());
-            } else {
-                assert!(result.0[i] == digest_1[i]);
-            }
-        }
-        for i in 0..result.1.len() {
-            if i == 0 {
-                assert!(result.1[i] == get_expected_first_byte_2());
-            } else {
-                assert!(result.1[i] == digest_2[i]);
-            }
-        }
+    fn should_fail_to_split_merkle_proof_without_leaf_and_root() {
+        let proof =
+            generate_merkle_proof(2, &get_merkle_tree(get_sample_action_digests()).unwrap())
+                .unwrap();
+        assert!(split_merkle_proof(&proof[..1].to_vec()).is_err());
     }
 
     #[test]
-    fn should_hash_canonical_pair() {
-        let expected_result = "a26284468e89fe4a5cce763ca3b3d3d37d5fcb35f289c63f0558487ec57ace28";
-        let canonical_pair = get_sample_canonical_pair();
-        let result = hash_canonical_pair(canonical_pair);
-        assert!(result.to_string() == expected_result);
+    fn should_verify_merkle_proof_correctly() {
+        let tree = get_merkle_tree(get_sample_action_digests()).unwrap();
+        let root = get_merkle_root_from_tree(&tree);
+        (0..tree[0].len()).for_each(|index| {
+            let proof = generate_merkle_proof(index, &tree).unwrap();
+            assert!(verify_merkle_proof(&proof, &root).unwrap());
+        });
     }
 
     #[test]
-    fn should_serialize_a_simple_action_receipt_correctly() {
-        let expected_result =
-            "6cd473b189a292bd520cac3430cc7934273da81cc3417376194a5d757b4abdc8".to_string();
-        let result = ActionReceipt::new(
-            "eosio",
-            "a6a370c6569034a4cc41935dd88f83d1c64e0414580872f29d87f69fe7a5d769",
-            60725518,
-            12,
-            498637597,
-            10,
-            vec![FlatMap::new(AccountName::from_str("eosio").unwrap(), 59191700)],
-        )
-        .unwrap()
-        .to_digest();
-        assert!(hex::encode(result) == expected_result);
+    fn should_not_verify_merkle_proof_against_wrong_root() {
+        let tree = get_merkle_tree(get_sample_action_digests()).unwrap();
+        let proof = generate_merkle_proof(2, &tree).unwrap();
+        assert!(!verify_merkle_proof(&proof, &[0u8; 32]).unwrap());
+        assert!(verify_merkle_proof(&proof, &[0u8; 31]).is_err());
     }
 
     #[test]
-    fn should_get_merkle_root_for_an_even_number_of_action_receipts() {
-        // NOTE: Test vector = https://jungle.bloks.io/block/58316764
-        let expected_result = "2f013d3ed57c89f1824772d18a4a74c043574bad47e9c6f088136e7595511810";
-        let action_digest_1 = ActionReceipt::new(
-            "eosio",
-            "8e3e721a497dbae5e5fde0bb43e9086628809efaf102b763a3e9820adce9ce8f",
-            62815613,
-            12,
-            503056735,
-            10,
-            vec![FlatMap::new(AccountName::from_str("eosio").unwrap(), 61275209)],
-        )
-        .unwrap()
-        .to_digest();
-        let action_digest_2 = ActionReceipt::new(
-            "provabletokn",
-            "4b991cebb3e6667b242aca3fb011623cd8ce2be2e8c24958da551c7b3ba68903",
-            2884,
-            48,
-            503056736,
-            80,
-            vec![FlatMap::new(AccountName::from_str("provabletokn").unwrap(), 3090)],
-        )
-        .unwrap()
-        .to_digest();
-        let result = get_merkle_digest(vec![action_digest_1, action_digest_2]);
-        assert!(hex::encode(result) == expected_result);
+    fn should_generate_and_verify_multi_proofs_for_many_index_sets() {
+        let merkle_tree = get_sample_merkle_tree_n(2);
+        let num_leaves = merkle_tree[0].len();
+        let action_mroot = get_sample_eos_block_n(2).unwrap().action_mroot.to_vec();
+        let index_sets: Vec<Vec<usize>> = vec![
+            vec![0],
+            vec![num_leaves - 1],
+            vec![140],
+            vec![0, 1],
+            vec![3, 140, 219, 220],
+            (0..num_leaves).step_by(7).collect(),
+            (0..num_leaves).collect(),
+        ];
+        index_sets.iter().for_each(|indices| {
+            let multi_proof = generate_merkle_multi_proof(indices, &merkle_tree).unwrap();
+            let leaves = get_leaves(&merkle_tree, &multi_proof.leaf_indices);
+            assert!(verify_merkle_multi_proof(&leaves, &multi_proof, &action_mroot).unwrap());
+        });
     }
 
     #[test]
-    fn should_get_merkle_root_for_an_odd_number_of_action_receipts_gt_one() {
-        // NOTE: Test vector = https://jungle.bloks.io/block/58319528
-        let expected_result = "593f54cbc0b877b30cec5e510838b2b16ca00aca43e21d204d21eb8e8f947aa0";
-        let action_digest_1 = ActionReceipt::new(
-            "eosio",
-            "23ab74b930cceea6061e1c4580ec988bf483a77e225cfca254d832928b4d1b36",
-            62818486,
-            12,
-            503062766,
-            10,
-            vec![FlatMap::new(AccountName::from_str("eosio").unwrap(), 61277973)],
-        )
-        .unwrap()
-        .to_digest();
-        let action_digest_2 = ActionReceipt::new(
-            "eosebetbullj",
-            "b9243d8513e25705e89d7ccd0491f4a57d07b9866fd89d3446887af852cfed15",
-            1440226,
-            215,
-            503062767,
-            215,
-            vec![FlatMap::new(AccountName::from_str("eosebetbullj").unwrap(), 1440687)],
-        )
-        .unwrap()
-        .to_digest();
-        let action_digest_3 = ActionReceipt::new(
-            "dvmh1tbb1him",
-            "4bd1d3e987cd13e3d108a9a0cd185bf022cb1a826f69f163fcd109db54ba799f",
-            804629,
-            2,
-            503062768,
-            1,
-            vec![FlatMap::new(AccountName::from_str("dvmh1tbb1him").unwrap(), 804649)],
-        )
-        .unwrap()
-        .to_digest();
-        let result = get_merkle_digest(vec![action_digest_1, action_digest_2, action_digest_3]);
-        assert!(hex::encode(result) == expected_result);
+    fn multi_proof_should_deduplicate_sibling_nodes() {
+        let merkle_tree = get_sample_merkle_tree_n(2);
+        let indices = vec![3, 4, 140, 141, 219];
+        let multi_proof = generate_merkle_multi_proof(&indices, &merkle_tree).unwrap();
+        let num_single_proof_nodes: usize = indices
+            .iter()
+            .map(|i| generate_merkle_proof(*i, &merkle_tree).unwrap().len() - 2)
+            .sum();
+        assert!(multi_proof.nodes.len() < num_single_proof_nodes);
+        let all_indices = (0..merkle_tree[0].len()).collect::<Vec<usize>>();
+        let full_multi_proof = generate_merkle_multi_proof(&all_indices, &merkle_tree).unwrap();
+        assert!(full_multi_proof.nodes.is_empty());
     }
 
     #[test]
-    fn should_get_action_mroot_when_action_has_gt_one_auth_sequence() {
-        // NOTE: Test vector = https://jungle.bloks.io/block/58345436
-        let expected_result = "f93a91688d12170c24807d4bd507cf52dcde962ae4a41a86fe55231dee4df348";
-        let action_receipt_1 = ActionReceipt::new(
-            "eosio",
-            "2d5371b958af052629f3fb62ede1bfcd94703675bc734535bf87fb615284dba3",
-            62844592,
-            12,
-            503124645,
-            10,
-            vec![FlatMap::new(AccountName::from_str("eosio").unwrap(), 61303891)],
-        )
-        .unwrap()
-        .to_digest();
-        let action_receipt_2 = ActionReceipt::new(
-            "authsequence",
-            "ae341469a7b3936c70e9684a42ef8fc1975f1bb2fe1f3b0b1105eda7d3a6276a",
-            10,
-            1,
-            503124646,
-            1,
-            vec![
-                FlatMap::new(AccountName::from_str("othrsequence").unwrap(), 14),
-                FlatMap::new(AccountName::from_str("rick11111111").unwrap(), 268),
-            ],
-        )
-        .unwrap()
-        .to_digest();
-        let result = get_merkle_digest(vec![action_receipt_1, action_receipt_2]);
-        assert!(hex::encode(result) == expected_result);
+    fn multi_proof_should_sort_and_deduplicate_indices() {
+        let merkle_tree = get_sample_merkle_tree_n(1);
+        let result = generate_merkle_multi_proof(&[5, 1, 5], &merkle_tree).unwrap();
+        assert_eq!(result.leaf_indices, vec![1, 5]);
+        assert_eq!(result.num_leaves, 6);
     }
 
     #[test]
-    fn should_get_action_mroot_for_four_actions_correctly() {
-        let digests = get_sample_action_digests();
-        let expected_result = "8b4e5e5d3e7587065896d0076d65c72e03c11a9159d414eb3a2363b59108116a";
-        let result = get_merkle_digest(digests);
-        assert!(hex::encode(result) == expected_result);
+    fn should_fail_to_generate_multi_proof_for_bad_indices() {
+        let merkle_tree = get_sample_merkle_tree_n(1);
+        assert!(generate_merkle_multi_proof(&[], &merkle_tree).is_err());
+        assert!(generate_merkle_multi_proof(&[1, 6], &merkle_tree).is_err());
     }
 
     #[test]
-    fn should_generate_merkle_proof_correctly() {
-        let index = 2;
-        let digests = get_sample_action_digests();
-        let expected_result = vec![
-            "41a91de4e161bc10ff6f7173822cf3f6417dd9bfc3dc88cf8bdd8196322837ce",
-            "d14a4b2157aaaa4fec077069a8617c53b4e3f75c142a6aea5aa24bd031578e96",
-            "3d8ddd7684e1f38a0f3c6880d179fc43ae730f9363eafe842cc85484450e2613",
-            "8b4e5e5d3e7587065896d0076d65c72e03c11a9159d414eb3a2363b59108116a",
-        ];
-        let result = generate_merkle_proof(index, digests).unwrap();
-        for i in 0..expected_result.len() {
-            assert!(expected_result[i] == result[i]);
-        }
+    fn tampered_multi_proof_should_not_verify() {
+        let merkle_tree = get_sample_merkle_tree_n(2);
+        let action_mroot = get_sample_eos_block_n(2).unwrap().action_mroot.to_vec();
+        let multi_proof = generate_merkle_multi_proof(&[3, 140, 200], &merkle_tree).unwrap();
+        let leaves = get_leaves(&merkle_tree, &multi_proof.leaf_indices);
+        let mut tampered_leaves = leaves.clone();
+        tampered_leaves[1] = merkle_tree[0][141];
+        assert!(!verify_merkle_multi_proof(&tampered_leaves, &multi_proof, &action_mroot).unwrap());
+        let mut tampered_proof = multi_proof.clone();
+        tampered_proof.nodes[0] = hex::encode([0u8; 32]);
+        assert!(!verify_merkle_multi_proof(&leaves, &tampered_proof, &action_mroot).unwrap());
+        let mut short_proof = multi_proof.clone();
+        short_proof.nodes.pop();
+        assert!(get_merkle_root_from_multi_proof(&leaves, &short_proof).is_err());
+        let mut long_proof = multi_proof;
+        long_proof.nodes.push(hex::encode([0u8; 32]));
+        assert!(get_merkle_root_from_multi_proof(&leaves, &long_proof).is_err());
+        assert!(get_merkle_root_from_multi_proof(&leaves[1..], &long_proof).is_err());
     }
 
     #[test]
-    fn should_verify_merkle_proof_correctly() {
-        let index = 2;
-        let digests = get_sample_action_digests();
-        let proof = generate_merkle_proof(index, digests).unwrap();
-        let result = verify_merkle_proof(&proof).unwrap();
-        assert!(result);
+    fn should_fail_to_verify_multi_proof_with_empty_or_short_node() {
+        let merkle_tree = get_sample_merkle_tree_n(2);
+        let action_mroot = get_sample_eos_block_n(2).unwrap().action_mroot.to_vec();
+        let multi_proof = generate_merkle_multi_proof(&[3, 140, 200], &merkle_tree).unwrap();
+        let leaves = get_leaves(&merkle_tree, &multi_proof.leaf_indices);
+        let mut empty_node_proof = multi_proof.clone();
+        empty_node_proof.nodes[0] = "".to_string();
+        assert!(verify_merkle_multi_proof(&leaves, &empty_node_proof, &action_mroot).is_err());
+        let mut short_node_proof = multi_proof;
+        short_node_proof.nodes[0].truncate(62);
+        assert!(verify_merkle_multi_proof(&leaves, &short_node_proof, &action_mroot).is_err());
     }
 }
-*/
@@ -9,6 +9,7 @@ pub enum AppError {
     EosChainWriteError(eos_chain::WriteError),
     EosPrimitivesError(eos_chain::error::Error),
     EosPrimitivesNamesError(eos_chain::ParseNameError),
+    Secp256k1Error(secp256k1::Error),
 }
 
 impl fmt::Display for AppError {
@@ -23,6 +24,7 @@ impl fmt::Display for AppError {
             AppError::EosPrimitivesNamesError(ref e) => {
                 format!("Eos Primitives Names Error: {:?}", e)
             }
+            AppError::Secp256k1Error(ref e) => format!("Secp256k1 Error: {:?}", e),
         };
         f.write_fmt(format_args!("{}", msg))
     }
@@ -63,3 +65,9 @@ impl From<eos_chain::WriteError> for AppError {
         AppError::EosChainWriteError(e)
     }
 }
+
+impl From<secp256k1::Error> for AppError {
+    fn from(e: secp256k1::Error) -> AppError {
+        AppError::Secp256k1Error(e)
+    }
+}
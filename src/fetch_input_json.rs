@@ -0,0 +1,166 @@
+use crate::parse_cli_args::CliArgs;
+use eos_action_proof_maker::{
+    error::AppError,
+    trace_api::get_input_json_from_trace_api_block,
+    types::{EosBlockJson, ReceiptSelectorJson, Result, TraceApiBlockJson},
+};
+
+const GET_BLOCK_PATH: &str = "/v1/chain/get_block";
+const TRACE_API_GET_BLOCK_PATH: &str = "/v1/trace_api/get_block";
+
+fn post_json_to_rpc(rpc_url: &str, path: &str, body: serde_json::Value) -> Result<String> {
+    let url = format!("{}{}", rpc_url.trim_end_matches('/'), path);
+    info!("✔ Calling RPC endpoint @ url: {}...", url);
+    ureq::post(&url)
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .map_err(|e| AppError::Custom(format!("✘ Error calling RPC @ url: {}\n✘ {}", url, e)))
+        .and_then(|response| Ok(response.into_string()?))
+}
+
+fn get_block_json(rpc_url: &str, block_num: u64) -> Result<EosBlockJson> {
+    let mut json: serde_json::Value = serde_json::from_str(&post_json_to_rpc(
+        rpc_url,
+        GET_BLOCK_PATH,
+        serde_json::json!({ "block_num_or_id": block_num }),
+    )?)?;
+    // NOTE: `nodeos` names the block's id `id`, whereas the input JSON calls it `block_id`.
+    if json.get("block_id").is_none() {
+        json["block_id"] = json["id"].clone();
+    }
+    Ok(serde_json::from_value(json)?)
+}
+
+fn get_trace_api_block_json(rpc_url: &str, block_num: u64) -> Result<TraceApiBlockJson> {
+    Ok(serde_json::from_str(&post_json_to_rpc(
+        rpc_url,
+        TRACE_API_GET_BLOCK_PATH,
+        serde_json::json!({ "block_num": block_num }),
+    )?)?)
+}
+
+fn parse_block_num(cli_args: &CliArgs) -> Result<u64> {
+    cli_args.flag_block_num.parse().map_err(|_| {
+        AppError::Custom(format!(
+            "✘ Invalid block number: {}",
+            cli_args.flag_block_num
+        ))
+    })
+}
+
+fn get_selector_from_cli_args(cli_args: &CliArgs) -> Result<ReceiptSelectorJson> {
+    match cli_args.flag_tx_id.is_empty() {
+        false => Ok(ReceiptSelectorJson {
+            tx_id: Some(cli_args.flag_tx_id.clone()),
+            ..Default::default()
+        }),
+        true => serde_json::from_str(&cli_args.flag_selector).map_err(|e| {
+            AppError::Custom(format!(
+                "✘ Invalid selector JSON: {}\n✘ {}",
+                cli_args.flag_selector, e
+            ))
+        }),
+    }
+}
+
+pub fn fetch_input_json_and_put_in_cli_args(cli_args: CliArgs) -> Result<CliArgs> {
+    let block_num = parse_block_num(&cli_args)?;
+    let selector = get_selector_from_cli_args(&cli_args)?;
+    info!("✔ Fetching block #{} & its traces...", block_num);
+    let input_json = get_input_json_from_trace_api_block(
+        get_block_json(&cli_args.flag_rpc_url, block_num)?,
+        &get_trace_api_block_json(&cli_args.flag_rpc_url, block_num)?,
+        &selector,
+    )?;
+    let input_json_string = serde_json::to_string(&input_json)?;
+    cli_args.update_json_in_cli_args(input_json_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eos_action_proof_maker::{
+        read_input_file::read_file_to_string, GenerateOptions, ProofMaker,
+    };
+    use std::thread;
+    use tiny_http::{Response, Server};
+
+    const SAMPLE_PATH: &str = "src/test_utils/sample-material-1.json";
+    const SAMPLE_GET_BLOCK_PATH: &str = "src/test_utils/sample-get-block-1.json";
+    const SAMPLE_TRACE_API_BLOCK_PATH: &str = "src/test_utils/sample-trace-api-block-1.json";
+
+    fn start_mock_rpc_server() -> String {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let rpc_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = match request.url() {
+                    GET_BLOCK_PATH => {
+                        Response::from_string(read_file_to_string(SAMPLE_GET_BLOCK_PATH).unwrap())
+                    }
+                    TRACE_API_GET_BLOCK_PATH => Response::from_string(
+                        read_file_to_string(SAMPLE_TRACE_API_BLOCK_PATH).unwrap(),
+                    ),
+                    _ => Response::from_string("{}").with_status_code(404),
+                };
+                request.respond(response).unwrap();
+            }
+        });
+        rpc_url
+    }
+
+    fn get_sample_cli_args(rpc_url: String) -> CliArgs {
+        CliArgs {
+            cmd_fetch: true,
+            flag_rpc_url: rpc_url,
+            flag_block_num: "84756340".to_string(),
+            flag_selector: "{\"global_sequence\":584628427}".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_get_selector_from_cli_args() {
+        let mut cli_args = get_sample_cli_args("".to_string());
+        let result = get_selector_from_cli_args(&cli_args).unwrap();
+        assert_eq!(result.global_sequence, Some(584628427));
+        cli_args.flag_tx_id = "0xabcd".to_string();
+        let result = get_selector_from_cli_args(&cli_args).unwrap();
+        assert_eq!(result.tx_id, Some("0xabcd".to_string()));
+        assert_eq!(result.global_sequence, None);
+    }
+
+    #[test]
+    fn should_fail_to_get_malformed_selector_from_cli_args() {
+        let mut cli_args = get_sample_cli_args("".to_string());
+        cli_args.flag_selector = "{\"receiver\":".to_string();
+        let err = get_selector_from_cli_args(&cli_args)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("✘ Invalid selector JSON"));
+    }
+
+    #[test]
+    fn should_fetch_input_json_from_mock_rpc_and_generate_same_output_as_sample() {
+        let rpc_url = start_mock_rpc_server();
+        let cli_args = fetch_input_json_and_put_in_cli_args(get_sample_cli_args(rpc_url)).unwrap();
+        let result =
+            ProofMaker::generate_json_string(&cli_args.arg_JSON, GenerateOptions::default())
+                .unwrap();
+        let expected_result = ProofMaker::generate_json_string(
+            &read_file_to_string(SAMPLE_PATH).unwrap(),
+            GenerateOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn should_fail_to_fetch_from_missing_rpc_endpoint() {
+        let rpc_url = format!("{}/nope", start_mock_rpc_server());
+        let err = fetch_input_json_and_put_in_cli_args(get_sample_cli_args(rpc_url))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Error calling RPC"));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    error::AppError,
+    hex_utils::strip_hex_prefix,
+    proof_maker::{GenerateOptions, ProofMaker},
+    types::Result,
+};
+use std::{
+    any::Any,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+// NOTE: These mirror the exit codes of the CLI.
+pub const EOS_ACTION_PROOF_MAKER_SUCCESS: c_int = 0;
+pub const EOS_ACTION_PROOF_MAKER_ERROR: c_int = 1;
+
+unsafe fn get_str_from_ptr<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    match ptr.is_null() {
+        true => Err(AppError::Custom(format!("✘ `{}` pointer is null!", name))),
+        false => CStr::from_ptr(ptr)
+            .to_str()
+            .map_err(|_| AppError::Custom(format!("✘ `{}` is not valid UTF-8!", name))),
+    }
+}
+
+fn convert_string_to_c_string(string: String) -> CString {
+    // NOTE: Neither JSON output nor error messages contain nul bytes, but never panic across FFI.
+    CString::new(string.replace('\0', "")).unwrap_or_default()
+}
+
+fn get_panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_else(|| "unknown panic".to_string()),
+    }
+}
+
+fn catch_panic<F: FnOnce() -> Result<String>>(f: F) -> Result<String> {
+    // NOTE: Unwinding across the FFI boundary is undefined behaviour, so a panic becomes an error.
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(AppError::Custom(format!(
+            "✘ Panicked: {}",
+            get_panic_message(payload)
+        )))
+    })
+}
+
+unsafe fn write_result_to_out_ptr(result: Result<String>, out_ptr: *mut *mut c_char) -> c_int {
+    let (status, string) = match result {
+        Ok(output) => (EOS_ACTION_PROOF_MAKER_SUCCESS, output),
+        Err(e) => (EOS_ACTION_PROOF_MAKER_ERROR, e.to_string()),
+    };
+    if !out_ptr.is_null() {
+        *out_ptr = convert_string_to_c_string(string).into_raw();
+    }
+    status
+}
+
+/// Generates proof(s) exactly as the CLI's `generate` command does. On return, `*out` points to
+/// the output JSON, or to the error message if the returned status is non-zero. Free it via
+/// `eos_action_proof_maker_free_string`.
+///
+/// # Safety
+///
+/// `input_json` must be a valid nul-terminated string & `out` a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn eos_action_proof_maker_generate(
+    input_json: *const c_char,
+    all: bool,
+    multi_proof: bool,
+    out: *mut *mut c_char,
+) -> c_int {
+    let result = catch_panic(|| {
+        get_str_from_ptr(input_json, "input_json").and_then(|input_json_string| {
+            ProofMaker::generate_json_string(
+                input_json_string,
+                GenerateOptions { all, multi_proof },
+            )
+        })
+    });
+    write_result_to_out_ptr(result, out)
+}
+
+/// Verifies a proof exactly as the CLI's `verify` command does when passed an `--action-mroot`.
+/// On return, `*out` points to the verification JSON, or to the error message if the returned
+/// status is non-zero. Free it via `eos_action_proof_maker_free_string`.
+///
+/// # Safety
+///
+/// `output_json` & `action_mroot_hex` must be valid nul-terminated strings & `out` a valid
+/// pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn eos_action_proof_maker_verify(
+    output_json: *const c_char,
+    action_mroot_hex: *const c_char,
+    out: *mut *mut c_char,
+) -> c_int {
+    let result = catch_panic(|| {
+        get_str_from_ptr(output_json, "output_json").and_then(|output_json_string| {
+            let action_mroot_hex = get_str_from_ptr(action_mroot_hex, "action_mroot_hex")?;
+            ProofMaker::verify_json_string(
+                output_json_string,
+                &hex::decode(strip_hex_prefix(action_mroot_hex))?,
+            )
+        })
+    });
+    write_result_to_out_ptr(result, out)
+}
+
+/// Frees a string returned by this library. Passing null is a no-op.
+///
+/// # Safety
+///
+/// `string` must have been returned by this library & not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn eos_action_proof_maker_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        get_output_string_from_input_json_string, get_sample_eos_block_n,
+        get_sample_submission_string_n,
+    };
+    use std::ptr;
+
+    unsafe fn take_out_string(out: *mut c_char) -> String {
+        let string = CStr::from_ptr(out).to_str().unwrap().to_string();
+        eos_action_proof_maker_free_string(out);
+        string
+    }
+
+    #[test]
+    fn should_generate_output_identical_to_cli() {
+        let input_json_string = get_sample_submission_string_n(1).unwrap();
+        let input_json = CString::new(input_json_string.clone()).unwrap();
+        let mut out = ptr::null_mut();
+        let status =
+            unsafe { eos_action_proof_maker_generate(input_json.as_ptr(), false, false, &mut out) };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_SUCCESS);
+        let result = unsafe { take_out_string(out) };
+        let expected_result = get_output_string_from_input_json_string(input_json_string).unwrap();
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn should_verify_generated_output() {
+        let input_json = CString::new(get_sample_submission_string_n(1).unwrap()).unwrap();
+        let mut output = ptr::null_mut();
+        let mut out = ptr::null_mut();
+        let action_mroot_hex =
+            CString::new(hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot)).unwrap();
+        let status = unsafe {
+            eos_action_proof_maker_generate(input_json.as_ptr(), false, false, &mut output);
+            eos_action_proof_maker_verify(output, action_mroot_hex.as_ptr(), &mut out)
+        };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_SUCCESS);
+        let result = unsafe {
+            eos_action_proof_maker_free_string(output);
+            take_out_string(out)
+        };
+        assert!(result.contains(&format!(
+            "\"action_mroot\":\"{}\"",
+            action_mroot_hex.to_str().unwrap()
+        )));
+    }
+
+    #[test]
+    fn should_return_error_message_on_failure() {
+        let input_json = CString::new("{}").unwrap();
+        let mut out = ptr::null_mut();
+        let status =
+            unsafe { eos_action_proof_maker_generate(input_json.as_ptr(), false, false, &mut out) };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_ERROR);
+        assert!(!unsafe { take_out_string(out) }.is_empty());
+    }
+
+    #[test]
+    fn should_return_error_for_null_input() {
+        let mut out = ptr::null_mut();
+        let status =
+            unsafe { eos_action_proof_maker_generate(ptr::null(), false, false, &mut out) };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_ERROR);
+        assert!(unsafe { take_out_string(out) }.contains("null"));
+    }
+
+    #[test]
+    fn should_return_error_message_for_malformed_proof() {
+        let input_json = CString::new(get_sample_submission_string_n(1).unwrap()).unwrap();
+        let mut output = ptr::null_mut();
+        let mut out = ptr::null_mut();
+        let action_mroot_hex =
+            CString::new(hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot)).unwrap();
+        let status = unsafe {
+            eos_action_proof_maker_generate(input_json.as_ptr(), false, false, &mut output)
+        };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_SUCCESS);
+        let mut output_json: serde_json::Value =
+            serde_json::from_str(&unsafe { take_out_string(output) }).unwrap();
+        output_json["action_proof"][1] = "".into();
+        let malformed_output = CString::new(output_json.to_string()).unwrap();
+        let status = unsafe {
+            eos_action_proof_maker_verify(
+                malformed_output.as_ptr(),
+                action_mroot_hex.as_ptr(),
+                &mut out,
+            )
+        };
+        assert_eq!(status, EOS_ACTION_PROOF_MAKER_ERROR);
+        assert!(unsafe { take_out_string(out) }.contains("action proof"));
+    }
+
+    #[test]
+    fn should_convert_panic_to_error() {
+        let err = catch_panic(|| panic!("boom")).unwrap_err().to_string();
+        assert!(err.contains("Panicked: boom"));
+        let err = catch_panic(|| panic!("{}", "formatted boom"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Panicked: formatted boom"));
+    }
+}
@@ -1,71 +1,364 @@
 use crate::{
     error::AppError,
     get_action_digest::get_action_digest,
+    hex_utils::strip_hex_prefix,
+    parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+    parse_input_json::get_selector_jsons_from_input_json,
     state::State,
-    types::{Byte, EosActionReceipts, Result},
+    types::{
+        Byte, DigestScheme, EosActionReceiptJsons, EosActionReceipts, ReceiptSelectorJson, Result,
+    },
 };
 use eos_chain::Action as EosAction;
 
-// NOTE: There exists _first_ in every block a special action in the protocol itself...
-const ON_BLOCK_ACTION_INDEX: u32 = 0;
-
-fn get_index_of_action_digest(
+fn get_candidate_indices_of_action_digest(
     action_digest: &[Byte],
     action_receipts: &EosActionReceipts,
-) -> Result<u32> {
-    let index =
-        action_receipts
+) -> Vec<usize> {
+    action_receipts
+        .iter()
+        .enumerate()
+        .filter(|(_, receipt)| receipt.act_digest.as_bytes() == action_digest)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+pub fn action_receipt_matches_selector(
+    index: usize,
+    action_receipt_jsons: &EosActionReceiptJsons,
+    selector: &ReceiptSelectorJson,
+) -> bool {
+    let receipt_json = &action_receipt_jsons[index];
+    // NOTE: Any field left unset in the selector matches every receipt.
+    selector.receipt_index.iter().all(|i| *i == index)
+        && selector
+            .receiver
             .iter()
-            .enumerate()
-            .fold(ON_BLOCK_ACTION_INDEX, |mut acc, (i, receipt)| {
-                if receipt.act_digest.as_bytes() == action_digest {
-                    acc = i as u32
-                };
-                acc
-            });
-
-    if index == ON_BLOCK_ACTION_INDEX {
-        Err(AppError::Custom(
-            "✘ Could not find action digest in action receipts!".to_string(),
-        ))
-    } else {
-        Ok(index)
+            .all(|receiver| receiver == &receipt_json.receiver)
+        && selector
+            .global_sequence
+            .iter()
+            .all(|global_sequence| *global_sequence == receipt_json.global_sequence)
+        && selector.tx_id.iter().all(|tx_id| {
+            strip_hex_prefix(tx_id).eq_ignore_ascii_case(strip_hex_prefix(&receipt_json.tx_id))
+        })
+}
+
+fn get_candidates_string(
+    indices: &[usize],
+    action_receipt_jsons: &EosActionReceiptJsons,
+) -> String {
+    indices
+        .iter()
+        .map(|i| {
+            format!(
+                "✘   receipt_index: {}, receiver: {}, global_sequence: {}, tx_id: {}",
+                i,
+                action_receipt_jsons[*i].receiver,
+                action_receipt_jsons[*i].global_sequence,
+                action_receipt_jsons[*i].tx_id,
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn select_index_from_candidates(
+    candidate_indices: &[usize],
+    action_receipt_jsons: &EosActionReceiptJsons,
+    selector: &ReceiptSelectorJson,
+) -> Result<u32> {
+    let selected_indices = candidate_indices
+        .iter()
+        .cloned()
+        .filter(|i| action_receipt_matches_selector(*i, action_receipt_jsons, selector))
+        .collect::<Vec<usize>>();
+    match selected_indices.len() {
+        1 => Ok(selected_indices[0] as u32),
+        0 => Err(AppError::Custom(format!(
+            "✘ No action receipt with the action's digest matches the supplied selector!\n✘ Candidates:\n{}",
+            get_candidates_string(candidate_indices, action_receipt_jsons)
+        ))),
+        num_matches => Err(AppError::Custom(format!(
+            "✘ Action digest matches {} action receipts! Supply a `selector` to choose one.\n✘ Candidates:\n{}",
+            num_matches,
+            get_candidates_string(&selected_indices, action_receipt_jsons)
+        ))),
     }
 }
 
-fn get_index_of_action(action: &EosAction, action_receipts: &EosActionReceipts) -> Result<u32> {
-    get_index_of_action_digest(&get_action_digest(action, false)?, action_receipts)
-        .or_else(|_| get_index_of_action_digest(&get_action_digest(action, true)?, action_receipts))
+fn maybe_get_index_of_action_digest(
+    action_digest: &[Byte],
+    action_receipts: &EosActionReceipts,
+    action_receipt_jsons: &EosActionReceiptJsons,
+    selector: &ReceiptSelectorJson,
+) -> Result<Option<u32>> {
+    let candidate_indices = get_candidate_indices_of_action_digest(action_digest, action_receipts);
+    match candidate_indices.is_empty() {
+        true => Ok(None),
+        false => select_index_from_candidates(&candidate_indices, action_receipt_jsons, selector)
+            .map(Some),
+    }
 }
 
-pub fn find_index_of_action_and_put_in_state(state: State) -> Result<State> {
-    info!("✔ Finding index of action in `action_receipts`...");
-    get_index_of_action(state.get_eos_action()?, state.get_eos_action_receipts()?)
-        .and_then(|index| state.add_proof_index(index))
+fn get_index_and_digest_scheme_of_action(
+    action: &EosAction,
+    action_return_value: &[Byte],
+    action_receipts: &EosActionReceipts,
+    action_receipt_jsons: &EosActionReceiptJsons,
+    selector: &ReceiptSelectorJson,
+    maybe_digest_scheme: Option<DigestScheme>,
+) -> Result<(u32, DigestScheme)> {
+    // NOTE: Without an explicit scheme, the legacy one is tried first since it's the only one
+    // possible in blocks from before the `action_return_value` protocol feature was activated.
+    let digest_schemes = match maybe_digest_scheme {
+        Some(digest_scheme) => vec![digest_scheme],
+        None => vec![DigestScheme::Legacy, DigestScheme::ReturnValue],
+    };
+    for digest_scheme in digest_schemes {
+        if let Some(index) = maybe_get_index_of_action_digest(
+            &get_action_digest(action, digest_scheme, action_return_value)?,
+            action_receipts,
+            action_receipt_jsons,
+            selector,
+        )? {
+            debug!(
+                "✔ Action found at index {} via {:?} digest scheme!",
+                index, digest_scheme
+            );
+            return Ok((index, digest_scheme));
+        }
+    }
+    Err(AppError::Custom(
+        "✘ Could not find action digest in action receipts!".to_string(),
+    ))
+}
+
+pub fn find_indices_of_actions_and_put_in_state(state: State) -> Result<State> {
+    info!("✔ Finding indices of actions in `action_receipts`...");
+    let action_receipts = state.get_eos_action_receipts()?;
+    let input_json = state.get_eos_input_json()?;
+    // NOTE: Receipt indices refer to the receipts sorted by global sequence, so the JSONs must be too.
+    let action_receipt_jsons =
+        sort_action_receipt_jsons_by_global_sequence(&input_json.action_receipts);
+    let action_return_values = state.get_action_return_values()?;
+    let (indices, digest_schemes): (Vec<u32>, Vec<DigestScheme>) = state
+        .get_eos_actions()?
+        .iter()
+        .zip(action_return_values.iter())
+        .zip(get_selector_jsons_from_input_json(input_json)?.iter())
+        .map(|((action, action_return_value), selector)| {
+            get_index_and_digest_scheme_of_action(
+                action,
+                action_return_value,
+                action_receipts,
+                &action_receipt_jsons,
+                selector,
+                input_json.digest_scheme,
+            )
+        })
+        .collect::<Result<Vec<(u32, DigestScheme)>>>()?
+        .into_iter()
+        .unzip();
+    state
+        .add_proof_indices(indices)
+        .and_then(|state| state.add_digest_schemes(digest_schemes))
+}
+
+pub fn put_all_action_receipt_indices_in_state(state: State) -> Result<State> {
+    info!("✔ Using the index of every action receipt...");
+    let num_action_receipts = state.get_eos_action_receipts()?.len() as u32;
+    state.add_proof_indices((0..num_action_receipts).collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{get_sample_action_n, get_sample_action_receipts_n};
+    use crate::test_utils::{
+        get_sample_action_n, get_sample_action_receipts_n, get_sample_submission_json_n,
+    };
+
+    fn get_sample_action_receipt_jsons_n(n: usize) -> EosActionReceiptJsons {
+        sort_action_receipt_jsons_by_global_sequence(
+            &get_sample_submission_json_n(n).unwrap().action_receipts,
+        )
+    }
+
+    fn get_index_of_receipt_digest_in_sample_1(
+        receipt_index: usize,
+        selector: &ReceiptSelectorJson,
+    ) -> Result<Option<u32>> {
+        let action_receipts = get_sample_action_receipts_n(1).unwrap();
+        let action_digest = action_receipts[receipt_index]
+            .act_digest
+            .as_bytes()
+            .to_vec();
+        maybe_get_index_of_action_digest(
+            &action_digest,
+            &action_receipts,
+            &get_sample_action_receipt_jsons_n(1),
+            selector,
+        )
+    }
+
+    fn get_index_of_duplicated_digest_in_sample_1(
+        selector: &ReceiptSelectorJson,
+    ) -> Result<Option<u32>> {
+        // NOTE: Receipts 2, 3 & 4 of sample 1 share the same `act_digest` w/ different receivers.
+        get_index_of_receipt_digest_in_sample_1(2, selector)
+    }
 
     #[test]
     fn should_get_index_of_action() {
         let sample_num = 1;
-        let expected_result = 5;
+        let expected_result = (5, DigestScheme::Legacy);
         let action_receipts = get_sample_action_receipts_n(sample_num).unwrap();
         let action = get_sample_action_n(sample_num).unwrap();
-        let result = get_index_of_action(&action, &action_receipts).unwrap();
+        let result = get_index_and_digest_scheme_of_action(
+            &action,
+            &[],
+            &action_receipts,
+            &get_sample_action_receipt_jsons_n(sample_num),
+            &ReceiptSelectorJson::default(),
+            None,
+        )
+        .unwrap();
         assert_eq!(result, expected_result);
     }
 
     #[test]
     fn should_get_index_of_action_2() {
         let sample_num = 2;
-        let expected_result = 140;
+        let expected_result = (140, DigestScheme::ReturnValue);
         let action_receipts = get_sample_action_receipts_n(sample_num).unwrap();
         let action = get_sample_action_n(sample_num).unwrap();
-        let result = get_index_of_action(&action, &action_receipts).unwrap();
+        let result = get_index_and_digest_scheme_of_action(
+            &action,
+            &[],
+            &action_receipts,
+            &get_sample_action_receipt_jsons_n(sample_num),
+            &ReceiptSelectorJson::default(),
+            None,
+        )
+        .unwrap();
         assert_eq!(result, expected_result);
     }
+
+    #[test]
+    fn should_error_listing_candidates_if_action_digest_is_ambiguous() {
+        let err = get_index_of_duplicated_digest_in_sample_1(&ReceiptSelectorJson::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("matches 3 action receipts"));
+        ["ptokensbtc1a", "eosdtpbtcpos", "test1test2tt"]
+            .iter()
+            .for_each(|receiver| assert!(err.contains(receiver)));
+    }
+
+    #[test]
+    fn should_select_index_of_ambiguous_action_digest_by_receiver() {
+        let receipt_jsons = get_sample_action_receipt_jsons_n(1);
+        let selector = ReceiptSelectorJson {
+            receiver: Some(receipt_jsons[3].receiver.clone()),
+            ..Default::default()
+        };
+        let result = get_index_of_duplicated_digest_in_sample_1(&selector).unwrap();
+        assert_eq!(result, Some(3));
+    }
+
+    #[test]
+    fn should_select_index_of_ambiguous_action_digest_by_global_sequence() {
+        let receipt_jsons = get_sample_action_receipt_jsons_n(1);
+        let selector = ReceiptSelectorJson {
+            global_sequence: Some(receipt_jsons[4].global_sequence),
+            ..Default::default()
+        };
+        let result = get_index_of_duplicated_digest_in_sample_1(&selector).unwrap();
+        assert_eq!(result, Some(4));
+    }
+
+    #[test]
+    fn should_select_index_of_ambiguous_action_digest_by_receipt_index() {
+        let selector = ReceiptSelectorJson {
+            receipt_index: Some(2),
+            ..Default::default()
+        };
+        let result = get_index_of_duplicated_digest_in_sample_1(&selector).unwrap();
+        assert_eq!(result, Some(2));
+    }
+
+    #[test]
+    fn should_still_be_ambiguous_if_selecting_only_by_shared_tx_id() {
+        let receipt_jsons = get_sample_action_receipt_jsons_n(1);
+        let selector = ReceiptSelectorJson {
+            tx_id: Some(format!("0x{}", receipt_jsons[2].tx_id)),
+            ..Default::default()
+        };
+        assert!(get_index_of_duplicated_digest_in_sample_1(&selector).is_err());
+    }
+
+    #[test]
+    fn should_error_if_no_candidate_matches_selector() {
+        let selector = ReceiptSelectorJson {
+            receipt_index: Some(5),
+            ..Default::default()
+        };
+        let err = get_index_of_duplicated_digest_in_sample_1(&selector)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("matches the supplied selector"));
+    }
+
+    #[test]
+    fn should_get_index_of_on_block_action_digest_at_index_zero() {
+        let result =
+            get_index_of_receipt_digest_in_sample_1(0, &ReceiptSelectorJson::default()).unwrap();
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn should_get_none_if_action_digest_not_in_action_receipts() {
+        let action_receipts = get_sample_action_receipts_n(1).unwrap();
+        let result = maybe_get_index_of_action_digest(
+            &[0u8; 32],
+            &action_receipts,
+            &get_sample_action_receipt_jsons_n(1),
+            &ReceiptSelectorJson::default(),
+        )
+        .unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn should_only_use_digest_scheme_override_if_supplied() {
+        [
+            (1, DigestScheme::Legacy, 5),
+            (2, DigestScheme::ReturnValue, 140),
+        ]
+        .iter()
+        .for_each(|(sample_num, digest_scheme, expected_index)| {
+            let action_receipts = get_sample_action_receipts_n(*sample_num).unwrap();
+            let action = get_sample_action_n(*sample_num).unwrap();
+            let action_receipt_jsons = get_sample_action_receipt_jsons_n(*sample_num);
+            let get_result = |maybe_digest_scheme| {
+                get_index_and_digest_scheme_of_action(
+                    &action,
+                    &[],
+                    &action_receipts,
+                    &action_receipt_jsons,
+                    &ReceiptSelectorJson::default(),
+                    maybe_digest_scheme,
+                )
+            };
+            let wrong_digest_scheme = match digest_scheme {
+                DigestScheme::Legacy => DigestScheme::ReturnValue,
+                DigestScheme::ReturnValue => DigestScheme::Legacy,
+            };
+            assert_eq!(
+                get_result(Some(*digest_scheme)).unwrap(),
+                (*expected_index, *digest_scheme)
+            );
+            assert!(get_result(Some(wrong_digest_scheme)).is_err());
+        });
+    }
 }
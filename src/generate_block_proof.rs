@@ -0,0 +1,181 @@
+use crate::{
+    error::AppError,
+    get_block_id::serialize_block_header_json,
+    hex_utils::{convert_bytes_to_checksum256, decode_checksum256},
+    incremental_merkle::IncrementalMerkle,
+    parse_eos_block::get_block_num_from_block_id,
+    state::State,
+    types::{Checksum256Bytes, MerkleProof, Result},
+};
+
+fn decode_later_block_id(block_id: &str, expected_block_num: u64) -> Result<Checksum256Bytes> {
+    match get_block_num_from_block_id(block_id)? == expected_block_num {
+        true => decode_checksum256(block_id),
+        false => Err(AppError::Custom(format!(
+            "✘ Later block id {} is not that of block {}!",
+            block_id, expected_block_num
+        ))),
+    }
+}
+
+fn generate_block_proof(state: &State, later_block_ids: &[String]) -> Result<MerkleProof> {
+    info!("✔ Generating proof of block id against a later block's `blockroot_merkle`...");
+    let eos_block = state.get_eos_block()?;
+    let block_header_state = state
+        .get_eos_input_json()?
+        .block_header_state
+        .as_ref()
+        .ok_or_else(|| {
+            AppError::Custom(
+                "✘ A `block_header_state` is needed to prove the block against a later block!"
+                    .to_string(),
+            )
+        })?;
+    let blockroot_merkle = IncrementalMerkle::from_json(&block_header_state.blockroot_merkle)?;
+    // NOTE: A block's `blockroot_merkle` holds the ids of every block before it.
+    if blockroot_merkle.get_node_count() + 1 != eos_block.block_num {
+        return Err(AppError::Custom(format!(
+            "✘ The `blockroot_merkle` of block {} should hold {} block ids, not {}!",
+            eos_block.block_num,
+            eos_block.block_num.saturating_sub(1),
+            blockroot_merkle.get_node_count()
+        )));
+    };
+    let block_ids = std::iter::once(convert_bytes_to_checksum256(&eos_block.block_id))
+        .chain(
+            later_block_ids
+                .iter()
+                .zip(eos_block.block_num + 1..)
+                .map(|(block_id, block_num)| decode_later_block_id(block_id, block_num)),
+        )
+        .collect::<Result<Vec<Checksum256Bytes>>>()?;
+    let block_proof = blockroot_merkle.generate_proof(&block_ids)?;
+    info!(
+        "✔ Block proven against the `blockroot_merkle` of block {}!",
+        eos_block.block_num + block_ids.len() as u64
+    );
+    Ok(block_proof)
+}
+
+pub fn maybe_generate_block_proof_and_put_in_state(state: State) -> Result<State> {
+    let block_proof = match &state.get_eos_input_json()?.later_block_ids {
+        Some(later_block_ids) => Some(generate_block_proof(&state, later_block_ids)?),
+        None => None,
+    };
+    match block_proof {
+        Some(block_proof) => state.add_block_proof(block_proof),
+        None => Ok(state),
+    }
+}
+
+/// Gets the block proof, if one was made, plus the serialized header tying the block's id to its
+/// `action_mroot`.
+pub fn get_block_proof_and_serialized_block_header(
+    state: &State,
+) -> Result<(Option<MerkleProof>, Option<String>)> {
+    match &state.block_proof {
+        Some(block_proof) => Ok((
+            Some(block_proof.clone()),
+            Some(hex::encode(serialize_block_header_json(
+                &state.get_eos_input_json()?.block,
+            )?)),
+        )),
+        None => Ok((None, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eos_merkle_utils::verify_merkle_proof,
+        parse_eos_block::parse_eos_block_json_and_put_in_state,
+        test_utils::{get_sample_later_block_ids_n, get_sample_signed_submission_json_n},
+        types::EosInputJson,
+    };
+
+    fn get_state_with_block_proof(input_json: EosInputJson) -> Result<State> {
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json))
+            .and_then(parse_eos_block_json_and_put_in_state)
+            .and_then(maybe_generate_block_proof_and_put_in_state)
+    }
+
+    fn get_sample_anchor_blockroot(input_json: &EosInputJson) -> Checksum256Bytes {
+        let mut blockroot_merkle = IncrementalMerkle::from_json(
+            &input_json
+                .block_header_state
+                .as_ref()
+                .unwrap()
+                .blockroot_merkle,
+        )
+        .unwrap();
+        blockroot_merkle.append(decode_checksum256(&input_json.block.block_id).unwrap());
+        input_json
+            .later_block_ids
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|block_id| blockroot_merkle.append(decode_checksum256(block_id).unwrap()))
+            .last()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_prove_block_against_later_blockroot() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        input_json.later_block_ids = Some(get_sample_later_block_ids_n(1).unwrap());
+        let anchor_blockroot = get_sample_anchor_blockroot(&input_json);
+        let state = get_state_with_block_proof(input_json.clone()).unwrap();
+        let block_proof = state.block_proof.as_ref().unwrap();
+        assert_eq!(block_proof[0], input_json.block.block_id);
+        assert!(verify_merkle_proof(block_proof, &anchor_blockroot).unwrap());
+        let (_, serialized_block_header) =
+            get_block_proof_and_serialized_block_header(&state).unwrap();
+        assert!(serialized_block_header.is_some());
+    }
+
+    #[test]
+    fn should_not_prove_block_without_later_block_ids() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let state = get_state_with_block_proof(input_json).unwrap();
+        assert!(state.block_proof.is_none());
+        assert_eq!(
+            get_block_proof_and_serialized_block_header(&state).unwrap(),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn should_fail_to_prove_block_with_non_consecutive_later_block_ids() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let mut later_block_ids = get_sample_later_block_ids_n(1).unwrap();
+        later_block_ids.remove(1);
+        input_json.later_block_ids = Some(later_block_ids);
+        let err = get_state_with_block_proof(input_json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not that of block 84756342"));
+    }
+
+    #[test]
+    fn should_fail_to_prove_block_with_blockroot_merkle_of_another_block() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        input_json.later_block_ids = Some(get_sample_later_block_ids_n(1).unwrap());
+        input_json.block_header_state = None;
+        assert!(get_state_with_block_proof(input_json.clone()).is_err());
+        let mut block_header_state = get_sample_signed_submission_json_n(1)
+            .unwrap()
+            .block_header_state
+            .unwrap();
+        let mut blockroot_merkle =
+            IncrementalMerkle::from_json(&block_header_state.blockroot_merkle).unwrap();
+        blockroot_merkle.append([0; 32]);
+        block_header_state.blockroot_merkle = blockroot_merkle.to_json();
+        input_json.block_header_state = Some(block_header_state);
+        let err = get_state_with_block_proof(input_json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("should hold 84756339 block ids"));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    eos_merkle_utils::{generate_merkle_multi_proof, verify_merkle_multi_proof},
+    error::AppError,
+    generate_block_proof::get_block_proof_and_serialized_block_header,
+    get_action_digest::get_action_digest,
+    state::State,
+    types::{MultiProofOutput, Result},
+};
+use eos_chain::SerializeData;
+
+pub fn generate_multi_proof_output(state: &State) -> Result<MultiProofOutput> {
+    info!("✔ Generating multi-proof...");
+    let actions = state.get_eos_actions()?;
+    let action_return_values = state.get_action_return_values()?;
+    let digest_schemes = state.get_digest_schemes()?;
+    let merkle_tree = state.get_merkle_tree()?;
+    let proof_indices = state.get_proof_indices()?;
+    let action_receipts = state.get_eos_action_receipts()?;
+    let eos_block = state.get_eos_block()?;
+    let action_mroot = eos_block.action_mroot.to_vec();
+    let indices = proof_indices
+        .iter()
+        .map(|index| *index as usize)
+        .collect::<Vec<usize>>();
+    let multi_proof = generate_merkle_multi_proof(&indices, merkle_tree)?;
+    let leaves = multi_proof
+        .leaf_indices
+        .iter()
+        .map(|index| merkle_tree[0][*index])
+        .collect::<Vec<_>>();
+    if !verify_merkle_multi_proof(&leaves, &multi_proof, &action_mroot)? {
+        return Err(AppError::Custom(
+            "✘ Error verifying multi-proof against `action_mroot`!".to_string(),
+        ));
+    }
+    info!("✔ Multi-proof verified!");
+    // NOTE: Everything is ordered as per the multi-proof's sorted & deduplicated `leaf_indices`.
+    let action_indices = multi_proof
+        .leaf_indices
+        .iter()
+        .map(|leaf_index| {
+            indices
+                .iter()
+                .position(|index| index == leaf_index)
+                .ok_or_else(|| AppError::Custom("✘ No action found for leaf index!".to_string()))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+    let serialized_actions = action_indices
+        .iter()
+        .map(|i| Ok(hex::encode(actions[*i].to_serialize_data()?)))
+        .collect::<Result<Vec<String>>>()?;
+    let action_digests = action_indices
+        .iter()
+        .map(|i| {
+            Ok(hex::encode(get_action_digest(
+                &actions[*i],
+                digest_schemes[*i],
+                &action_return_values[*i],
+            )?))
+        })
+        .collect::<Result<Vec<String>>>()?;
+    let serialized_action_receipts = multi_proof
+        .leaf_indices
+        .iter()
+        .map(|index| Ok(hex::encode(action_receipts[*index].to_serialize_data()?)))
+        .collect::<Result<Vec<String>>>()?;
+    let (block_proof, serialized_block_header) =
+        get_block_proof_and_serialized_block_header(state)?;
+    Ok(MultiProofOutput {
+        block_id: hex::encode(&eos_block.block_id),
+        block_num: eos_block.block_num,
+        timestamp: eos_block.timestamp.to_string(),
+        action_mroot: hex::encode(action_mroot),
+        serialized_actions,
+        action_digests,
+        digest_schemes: action_indices.iter().map(|i| digest_schemes[*i]).collect(),
+        action_receipt_digests: leaves.iter().map(hex::encode).collect(),
+        serialized_action_receipts,
+        action_multi_proof: multi_proof,
+        block_proof,
+        serialized_block_header,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        get_multi_proof_output_string_from_input_json_string, get_sample_action_receipts_n,
+        get_sample_batch_submission_string_n, get_sample_eos_block_n,
+    };
+
+    #[test]
+    fn should_generate_multi_proof_output_from_batch_submission() {
+        let input_json_string = get_sample_batch_submission_string_n(1).unwrap();
+        let output: MultiProofOutput = serde_json::from_str(
+            &get_multi_proof_output_string_from_input_json_string(input_json_string).unwrap(),
+        )
+        .unwrap();
+        let action_receipts = get_sample_action_receipts_n(1).unwrap();
+        assert_eq!(
+            output.action_mroot,
+            hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot)
+        );
+        assert_eq!(output.block_num, 84756340);
+        assert_eq!(output.timestamp, "2020-04-06T16:19:29.500");
+        assert_eq!(output.action_multi_proof.leaf_indices, vec![1, 5]);
+        assert_eq!(output.serialized_actions.len(), 2);
+        assert_eq!(output.digest_schemes.len(), 2);
+        output
+            .action_multi_proof
+            .leaf_indices
+            .iter()
+            .zip(output.serialized_action_receipts.iter())
+            .zip(output.action_digests.iter())
+            .for_each(|((index, serialized_receipt), action_digest)| {
+                assert_eq!(
+                    serialized_receipt,
+                    &hex::encode(action_receipts[*index].to_serialize_data().unwrap())
+                );
+                assert_eq!(
+                    action_digest,
+                    &action_receipts[*index].act_digest.to_string()
+                );
+            });
+    }
+}
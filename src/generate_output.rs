@@ -1,40 +1,157 @@
 use crate::{
-    action_return_values::action_return_value_protocol_feature_is_enabled,
+    generate_block_proof::get_block_proof_and_serialized_block_header,
     get_action_digest::get_action_digest,
+    parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+    parse_input_json::get_action_jsons_from_input_json,
     state::State,
-    types::{Output, Result},
+    types::{ActionReceiptOutput, ActionReceiptOutputs, Output, Outputs, Result},
 };
 use eos_chain::{Digest, SerializeData};
 
-pub fn generate_output_string(state: State) -> Result<String> {
-    let action = state.get_eos_action()?;
+pub fn generate_outputs(state: &State) -> Result<Outputs> {
+    let actions = state.get_eos_actions()?;
+    let action_return_values = state.get_action_return_values()?;
+    let proofs = state.get_merkle_proofs()?;
+    let digest_schemes = state.get_digest_schemes()?;
+    let proof_indices = state.get_proof_indices()?;
     let action_receipts = state.get_eos_action_receipts()?;
+    let eos_block = state.get_eos_block()?;
+    let block_id = hex::encode(&eos_block.block_id);
+    let action_jsons = get_action_jsons_from_input_json(state.get_eos_input_json()?)?;
+    let (block_proof, serialized_block_header) =
+        get_block_proof_and_serialized_block_header(state)?;
+    // NOTE: The proof index refers to the receipts sorted by global sequence, so the JSONs must be too.
+    let action_receipt_jsons =
+        sort_action_receipt_jsons_by_global_sequence(&state.get_eos_input_json()?.action_receipts);
 
-    let action_return_value_is_enabled =
-        action_return_value_protocol_feature_is_enabled(action, action_receipts)?;
+    (0..actions.len())
+        .map(|i| {
+            let index = proof_indices[i] as usize;
+            Ok(Output {
+                tx_id: action_receipt_jsons[index].tx_id.clone(),
+                block_id: block_id.clone(),
+                block_num: eos_block.block_num,
+                timestamp: eos_block.timestamp.to_string(),
+                action_index: index,
+                action_proof: proofs[i].clone(),
+                action_digest: format!(
+                    "0x{}",
+                    hex::encode(get_action_digest(
+                        &actions[i],
+                        digest_schemes[i],
+                        &action_return_values[i],
+                    )?)
+                ),
+                digest_scheme: Some(digest_schemes[i]),
+                serialized_action: hex::encode(actions[i].to_serialize_data()?),
+                action_json: action_jsons[i].clone(),
+                action_receipt_json: action_receipt_jsons[index].clone(),
+                action_receipt_digest: format!("0x{}", action_receipts[index].digest()?),
+                serialized_action_receipt: hex::encode(action_receipts[index].to_serialize_data()?),
+                return_value_hex_data: action_jsons[i].return_value_hex_data.clone(),
+                block_proof: block_proof.clone(),
+                serialized_block_header: serialized_block_header.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn generate_action_receipt_outputs(state: &State) -> Result<ActionReceiptOutputs> {
+    let proofs = state.get_merkle_proofs()?;
+    let action_receipts = state.get_eos_action_receipts()?;
+    let eos_block = state.get_eos_block()?;
+    let block_id = hex::encode(&eos_block.block_id);
+    let (block_proof, serialized_block_header) =
+        get_block_proof_and_serialized_block_header(state)?;
+    let action_receipt_jsons =
+        sort_action_receipt_jsons_by_global_sequence(&state.get_eos_input_json()?.action_receipts);
+    state
+        .get_proof_indices()?
+        .iter()
+        .zip(proofs.iter())
+        .map(|(index, proof)| {
+            let index = *index as usize;
+            Ok(ActionReceiptOutput {
+                tx_id: action_receipt_jsons[index].tx_id.clone(),
+                block_id: block_id.clone(),
+                block_num: eos_block.block_num,
+                timestamp: eos_block.timestamp.to_string(),
+                action_index: index,
+                action_proof: proof.clone(),
+                action_receipt_digest: format!("0x{}", action_receipts[index].digest()?),
+                serialized_action_receipt: hex::encode(action_receipts[index].to_serialize_data()?),
+                action_receipt_json: action_receipt_jsons[index].clone(),
+                block_proof: block_proof.clone(),
+                serialized_block_header: serialized_block_header.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eos_merkle_utils::verify_merkle_proof,
+        test_utils::{
+            get_action_receipt_outputs_string_from_input_json_string,
+            get_output_string_from_input_json_string, get_sample_batch_submission_string_n,
+            get_sample_eos_block_n, get_sample_output_n, get_sample_submission_string_n,
+        },
+    };
+
+    #[test]
+    fn should_generate_single_output_for_single_action() {
+        let output = get_sample_output_n(1).unwrap();
+        assert_eq!(output.action_index, 5);
+        assert_eq!(output.action_receipt_json.global_sequence, 584628427);
+        assert_eq!(output.block_num, 84756340);
+        assert_eq!(output.timestamp, "2020-04-06T16:19:29.500");
+    }
+
+    #[test]
+    fn should_generate_outputs_for_batch_of_actions() {
+        let expected_action_indices = vec![1, 5];
+        let outputs: Outputs = get_sample_batch_submission_string_n(1)
+            .and_then(get_output_string_from_input_json_string)
+            .and_then(|string| Ok(serde_json::from_str(&string)?))
+            .unwrap();
+        let action_indices = outputs
+            .iter()
+            .map(|output| output.action_index)
+            .collect::<Vec<usize>>();
+        assert_eq!(action_indices, expected_action_indices);
+        outputs.iter().for_each(|output| {
+            assert_eq!(
+                output.action_digest,
+                format!("0x{}", output.action_receipt_json.act_digest)
+            )
+        });
+    }
 
-    Ok(serde_json::to_string(&Output {
-        tx_id: state.get_eos_input_json()?.action_receipts[state.get_proof_index()? as usize]
-            .tx_id
-            .clone(),
-        block_id: hex::encode(&state.get_eos_block()?.block_id),
-        action_index: state.get_proof_index()? as usize,
-        action_proof: state.get_merkle_proof()?.to_vec(),
-        action_digest: format!(
-            "0x{}",
-            hex::encode(get_action_digest(action, action_return_value_is_enabled)?)
-        ),
-        serialized_action: hex::encode(action.to_serialize_data()?),
-        action_json: state.get_eos_input_json()?.action.clone(),
-        action_receipt_json: state.get_eos_input_json()?.action_receipts
-            [state.get_proof_index()? as usize]
-            .clone(),
-        action_receipt_digest: format!(
-            "0x{}",
-            action_receipts[state.get_proof_index()? as usize].digest()?,
-        ),
-        serialized_action_receipt: hex::encode(
-            action_receipts[state.get_proof_index()? as usize].to_serialize_data()?,
-        ),
-    })?)
+    #[test]
+    fn should_generate_outputs_for_every_action_receipt() {
+        let expected_num_outputs = 6;
+        let action_mroot = get_sample_eos_block_n(1).unwrap().action_mroot.to_vec();
+        let mut json: serde_json::Value =
+            serde_json::from_str(&get_sample_submission_string_n(1).unwrap()).unwrap();
+        json["action"] = serde_json::Value::Null;
+        let outputs: ActionReceiptOutputs =
+            get_action_receipt_outputs_string_from_input_json_string(json.to_string())
+                .and_then(|string| Ok(serde_json::from_str(&string)?))
+                .unwrap();
+        assert_eq!(outputs.len(), expected_num_outputs);
+        outputs.iter().enumerate().for_each(|(i, output)| {
+            assert_eq!(output.action_index, i);
+            assert_eq!(
+                output.action_receipt_digest,
+                format!("0x{}", output.action_proof[0])
+            );
+            assert!(verify_merkle_proof(&output.action_proof, &action_mroot).unwrap());
+        });
+        assert!(outputs
+            .windows(2)
+            .all(|pair| pair[0].action_receipt_json.global_sequence
+                < pair[1].action_receipt_json.global_sequence));
+    }
 }
@@ -1,51 +1,73 @@
 use crate::{
-    eos_merkle_utils::generate_merkle_proof,
+    eos_merkle_utils::{generate_merkle_proof, MerkleTree},
     state::State,
-    types::{Bytes, EosActionReceipts, MerkleProof, Result},
+    types::{MerkleProofs, Result},
 };
 
-use eos_chain::Digest;
-
-pub fn generate_merkle_proof_from_action_receipts(
-    index: u32,
-    action_receipts: &EosActionReceipts,
-) -> Result<MerkleProof> {
-    generate_merkle_proof(
-        index as usize,
-        action_receipts
-            .iter()
-            .map(|action_receipt| Ok(action_receipt.digest()?.as_bytes().to_vec()))
-            .collect::<Result<Vec<Bytes>>>()?,
-    )
+pub fn generate_merkle_proofs_from_tree(
+    indices: &[u32],
+    merkle_tree: &MerkleTree,
+) -> Result<MerkleProofs> {
+    indices
+        .iter()
+        .map(|index| generate_merkle_proof(*index as usize, merkle_tree))
+        .collect()
 }
 
-pub fn generate_proof_and_add_to_state(state: State) -> Result<State> {
+pub fn generate_proofs_and_add_to_state(state: State) -> Result<State> {
     state
-        .get_eos_action_receipts()
-        .and_then(|action_receipts| {
-            generate_merkle_proof_from_action_receipts(state.get_proof_index()?, action_receipts)
+        .get_merkle_tree()
+        .and_then(|merkle_tree| {
+            generate_merkle_proofs_from_tree(state.get_proof_indices()?, merkle_tree)
         })
-        .and_then(|proof| state.add_merkle_proof(proof))
+        .and_then(|proofs| state.add_merkle_proofs(proofs))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{
-        get_sample_action_receipts_n, get_sample_eos_block_n, get_sample_merkle_proof_n,
-        MERKLE_PROOF_INDEX,
+    use crate::{
+        build_merkle_tree::get_action_receipt_digests,
+        eos_merkle_utils::{get_merkle_tree, verify_merkle_proof},
+        test_utils::{
+            get_sample_action_receipts_n, get_sample_eos_block_n, get_sample_merkle_proof_n,
+            MERKLE_PROOF_INDEX,
+        },
     };
 
+    fn get_sample_merkle_tree_n(n: usize) -> MerkleTree {
+        get_merkle_tree(
+            get_action_receipt_digests(&get_sample_action_receipts_n(n).unwrap()).unwrap(),
+        )
+        .unwrap()
+    }
+
     #[test]
     fn should_generate_merkle_proof_from_actions_receipts() {
-        let expected_action_mroot = get_sample_eos_block_n(1).unwrap().action_mroot;
-        let action_receipts = get_sample_action_receipts_n(1).unwrap();
-        let result =
-            generate_merkle_proof_from_action_receipts(MERKLE_PROOF_INDEX, &action_receipts)
-                .unwrap();
+        let expected_action_mroot = hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot);
+        let merkle_tree = get_sample_merkle_tree_n(1);
+        let result = generate_merkle_proofs_from_tree(&[MERKLE_PROOF_INDEX], &merkle_tree)
+            .unwrap()
+            .remove(0);
         let expected_result = get_sample_merkle_proof_n(1).unwrap();
         assert!(result == expected_result);
         let last = expected_result.last().unwrap();
         assert!(last == &expected_action_mroot);
     }
+
+    #[test]
+    fn should_generate_valid_merkle_proofs_for_every_index_from_one_tree() {
+        let action_mroot = get_sample_eos_block_n(2).unwrap().action_mroot.to_vec();
+        let merkle_tree = get_sample_merkle_tree_n(2);
+        let indices = (0..merkle_tree[0].len() as u32).collect::<Vec<u32>>();
+        let proofs = generate_merkle_proofs_from_tree(&indices, &merkle_tree).unwrap();
+        assert_eq!(proofs.len(), indices.len());
+        proofs
+            .iter()
+            .zip(merkle_tree[0].iter())
+            .for_each(|(proof, leaf)| {
+                assert_eq!(proof[0], hex::encode(leaf));
+                assert!(verify_merkle_proof(proof, &action_mroot).unwrap());
+            });
+    }
 }
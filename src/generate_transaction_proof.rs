@@ -0,0 +1,338 @@
+use crate::{
+    eos_merkle_utils::{
+        generate_merkle_proof, get_merkle_root_from_tree, get_merkle_tree, verify_merkle_proof,
+    },
+    error::AppError,
+    generate_block_proof::get_block_proof_and_serialized_block_header,
+    get_block_id::serialize_signature,
+    hex_utils::{decode_checksum256, strip_hex_prefix, CHECKSUM256_LENGTH},
+    state::State,
+    types::{
+        Byte, Bytes, PackedTransactionJson, Result, TransactionProofOutput,
+        TransactionProofOutputs, TransactionReceiptJson, TransactionReceiptJsons,
+        TransactionReceiptTrxJson,
+    },
+    varuint_utils::serialize_varuint32,
+};
+use bitcoin_hashes::{sha256, Hash};
+
+const UNCOMPRESSED: &str = "none";
+
+fn get_transaction_status_byte(status: &str) -> Result<Byte> {
+    match status {
+        "executed" => Ok(0),
+        "soft_fail" => Ok(1),
+        "hard_fail" => Ok(2),
+        "delayed" => Ok(3),
+        "expired" => Ok(4),
+        _ => Err(AppError::Custom(format!(
+            "✘ Invalid transaction receipt `status`: {}",
+            status
+        ))),
+    }
+}
+
+fn get_compression_byte(compression: &str) -> Result<Byte> {
+    match compression {
+        UNCOMPRESSED => Ok(0),
+        "zlib" => Ok(1),
+        _ => Err(AppError::Custom(format!(
+            "✘ Invalid packed transaction `compression`: {}",
+            compression
+        ))),
+    }
+}
+
+fn decode_hex_field(field: &str, hex: &str) -> Result<Bytes> {
+    hex::decode(strip_hex_prefix(hex)).map_err(|_| {
+        AppError::Custom(format!(
+            "✘ Invalid transaction receipt `{}`: {}",
+            field, hex
+        ))
+    })
+}
+
+fn decode_transaction_id(tx_id: &str) -> Result<Bytes> {
+    decode_checksum256(tx_id)
+        .map(|checksum| checksum.to_vec())
+        .map_err(|_| {
+            AppError::Custom(format!(
+                "✘ Transaction id must be {} bytes: {}",
+                CHECKSUM256_LENGTH, tx_id
+            ))
+        })
+}
+
+fn serialize_bytes(bytes: &[Byte]) -> Bytes {
+    [serialize_varuint32(bytes.len() as u32), bytes.to_vec()].concat()
+}
+
+fn get_packed_transaction_digest(packed_transaction: &PackedTransactionJson) -> Result<Bytes> {
+    let packed_trx = decode_hex_field("packed_trx", &packed_transaction.packed_trx)?;
+    // NOTE: An uncompressed transaction's id is the hash of its packed bytes, so can be checked.
+    if packed_transaction.compression == UNCOMPRESSED
+        && sha256::Hash::hash(&packed_trx).to_vec()
+            != decode_transaction_id(&packed_transaction.id)?
+    {
+        return Err(AppError::Custom(format!(
+            "✘ Transaction id {} is not the hash of its `packed_trx`!",
+            packed_transaction.id
+        )));
+    };
+    let signatures = packed_transaction
+        .signatures
+        .iter()
+        .map(|signature| serialize_signature(signature))
+        .collect::<Result<Vec<Bytes>>>()?;
+    // NOTE: Signatures & context free data are prunable, so only their digest is committed to.
+    let prunable_data_digest = sha256::Hash::hash(
+        &[
+            serialize_varuint32(signatures.len() as u32),
+            signatures.concat(),
+            serialize_bytes(&decode_hex_field(
+                "packed_context_free_data",
+                &packed_transaction.packed_context_free_data,
+            )?),
+        ]
+        .concat(),
+    );
+    Ok(sha256::Hash::hash(
+        &[
+            vec![get_compression_byte(&packed_transaction.compression)?],
+            serialize_bytes(&packed_trx),
+            prunable_data_digest.to_vec(),
+        ]
+        .concat(),
+    )
+    .to_vec())
+}
+
+fn get_tx_id_from_transaction_receipt_json(transaction_receipt: &TransactionReceiptJson) -> &str {
+    match &transaction_receipt.trx {
+        TransactionReceiptTrxJson::Id(tx_id) => tx_id,
+        TransactionReceiptTrxJson::Packed(packed_transaction) => &packed_transaction.id,
+    }
+}
+
+/// Computes the digest of a transaction receipt, as committed to by a block's `transaction_mroot`.
+pub fn get_transaction_receipt_digest(
+    transaction_receipt: &TransactionReceiptJson,
+) -> Result<Bytes> {
+    let trx_digest = match &transaction_receipt.trx {
+        TransactionReceiptTrxJson::Id(tx_id) => decode_transaction_id(tx_id)?,
+        TransactionReceiptTrxJson::Packed(packed_transaction) => {
+            get_packed_transaction_digest(packed_transaction)?
+        }
+    };
+    Ok(sha256::Hash::hash(
+        &[
+            vec![get_transaction_status_byte(&transaction_receipt.status)?],
+            transaction_receipt.cpu_usage_us.to_le_bytes().to_vec(),
+            serialize_varuint32(transaction_receipt.net_usage_words),
+            trx_digest,
+        ]
+        .concat(),
+    )
+    .to_vec())
+}
+
+fn get_transaction_receipt_jsons(state: &State) -> Result<&TransactionReceiptJsons> {
+    match &state.get_eos_input_json()?.block.transactions {
+        Some(transaction_receipts) if !transaction_receipts.is_empty() => Ok(transaction_receipts),
+        _ => Err(AppError::Custom(
+            "✘ Block has no `transactions` to prove!".to_string(),
+        )),
+    }
+}
+
+fn find_index_of_transaction(
+    transaction_receipts: &[TransactionReceiptJson],
+    tx_id: &str,
+) -> Result<usize> {
+    transaction_receipts
+        .iter()
+        .position(|transaction_receipt| {
+            strip_hex_prefix(get_tx_id_from_transaction_receipt_json(transaction_receipt))
+                .eq_ignore_ascii_case(strip_hex_prefix(tx_id))
+        })
+        .ok_or_else(|| {
+            AppError::Custom(format!("✘ No transaction with id {} in the block!", tx_id))
+        })
+}
+
+/// Generates & verifies a proof tying each transaction receipt in the block, or only that of the
+/// transaction with the given id, to the block's `transaction_mroot`.
+pub fn generate_transaction_proof_outputs(
+    state: &State,
+    tx_id: Option<&str>,
+) -> Result<TransactionProofOutputs> {
+    info!("✔ Generating transaction proofs against the block's `transaction_mroot`...");
+    let eos_block = state.get_eos_block()?;
+    let transaction_receipts = get_transaction_receipt_jsons(state)?;
+    let tree = get_merkle_tree(
+        transaction_receipts
+            .iter()
+            .map(get_transaction_receipt_digest)
+            .collect::<Result<Vec<Bytes>>>()?,
+    )?;
+    let transaction_mroot = get_merkle_root_from_tree(&tree);
+    if transaction_mroot != eos_block.transaction_mroot {
+        return Err(AppError::Custom(format!(
+            "✘ Error validating transaction receipts!\n✘ Their merkle root {} does NOT match the block's `transaction_mroot`!",
+            hex::encode(transaction_mroot)
+        )));
+    };
+    let indices = match tx_id {
+        Some(tx_id) => vec![find_index_of_transaction(transaction_receipts, tx_id)?],
+        None => (0..transaction_receipts.len()).collect(),
+    };
+    let (block_proof, serialized_block_header) =
+        get_block_proof_and_serialized_block_header(state)?;
+    indices
+        .into_iter()
+        .map(|index| {
+            let transaction_proof = generate_merkle_proof(index, &tree)?;
+            if !verify_merkle_proof(&transaction_proof, &transaction_mroot)? {
+                return Err(AppError::Custom(format!(
+                    "✘ Transaction proof for receipt {} is NOT valid!",
+                    index
+                )));
+            };
+            Ok(TransactionProofOutput {
+                tx_id: get_tx_id_from_transaction_receipt_json(&transaction_receipts[index])
+                    .to_string(),
+                block_id: hex::encode(&eos_block.block_id),
+                block_num: eos_block.block_num,
+                timestamp: eos_block.timestamp.to_string(),
+                transaction_index: index,
+                transaction_mroot: hex::encode(transaction_mroot),
+                transaction_receipt_digest: format!("0x{}", hex::encode(tree[0][index])),
+                transaction_proof,
+                transaction_receipt_json: transaction_receipts[index].clone(),
+                block_proof: block_proof.clone(),
+                serialized_block_header: serialized_block_header.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_eos_block::parse_eos_block_json_and_put_in_state,
+        test_utils::{get_sample_eos_block_n, get_sample_submission_json_n},
+        types::EosInputJson,
+    };
+
+    const SAMPLE_TX_ID: &str = "9051ddcb328050217c59b61a6cd1ec8435146bf3da5d74de1c27a5ab6e771539";
+
+    fn get_state(input_json: EosInputJson) -> Result<State> {
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json))
+            .and_then(parse_eos_block_json_and_put_in_state)
+    }
+
+    fn get_sample_input_json_with_deferred_transactions() -> EosInputJson {
+        // NOTE: Adds the receipts of some deferred transactions & updates the `transaction_mroot`.
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        let transaction_receipts = input_json.block.transactions.as_mut().unwrap();
+        (1..5u8).for_each(|i| {
+            transaction_receipts.push(TransactionReceiptJson {
+                status: "delayed".to_string(),
+                cpu_usage_us: 100 * i as u32,
+                net_usage_words: 0,
+                trx: TransactionReceiptTrxJson::Id(hex::encode([i; CHECKSUM256_LENGTH])),
+            })
+        });
+        input_json.block.transaction_mroot = hex::encode(get_merkle_root_from_tree(
+            &get_merkle_tree(
+                transaction_receipts
+                    .iter()
+                    .map(|receipt| get_transaction_receipt_digest(receipt).unwrap())
+                    .collect(),
+            )
+            .unwrap(),
+        ));
+        input_json
+    }
+
+    #[test]
+    fn should_get_transaction_receipt_digest_matching_sample_transaction_mroot() {
+        let input_json = get_sample_submission_json_n(1).unwrap();
+        let transaction_receipts = input_json.block.transactions.unwrap();
+        assert_eq!(transaction_receipts.len(), 1);
+        let result = get_transaction_receipt_digest(&transaction_receipts[0]).unwrap();
+        // NOTE: A lone leaf is its own merkle root.
+        assert_eq!(
+            result,
+            get_sample_eos_block_n(1)
+                .unwrap()
+                .transaction_mroot
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn should_generate_transaction_proof_for_every_receipt() {
+        let input_json = get_sample_input_json_with_deferred_transactions();
+        let transaction_mroot = hex::decode(&input_json.block.transaction_mroot).unwrap();
+        let outputs =
+            generate_transaction_proof_outputs(&get_state(input_json).unwrap(), None).unwrap();
+        assert_eq!(outputs.len(), 5);
+        outputs.iter().enumerate().for_each(|(i, output)| {
+            assert_eq!(output.transaction_index, i);
+            assert_eq!(
+                output.transaction_proof[0],
+                strip_hex_prefix(&output.transaction_receipt_digest)
+            );
+            assert!(verify_merkle_proof(&output.transaction_proof, &transaction_mroot).unwrap());
+        });
+        assert_eq!(outputs[0].tx_id, SAMPLE_TX_ID);
+    }
+
+    #[test]
+    fn should_generate_transaction_proof_for_tx_id() {
+        let state = get_state(get_sample_input_json_with_deferred_transactions()).unwrap();
+        let tx_id = format!(
+            "0x{}",
+            hex::encode([3u8; CHECKSUM256_LENGTH]).to_uppercase()
+        );
+        let outputs = generate_transaction_proof_outputs(&state, Some(&tx_id)).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].transaction_index, 3);
+        let err = generate_transaction_proof_outputs(&state, Some(&"00".repeat(32)))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("No transaction with id"));
+    }
+
+    #[test]
+    fn should_fail_to_generate_transaction_proof_for_tampered_receipts() {
+        let mut tampered_receipt_json = get_sample_submission_json_n(1).unwrap();
+        tampered_receipt_json.block.transactions.as_mut().unwrap()[0].cpu_usage_us += 1;
+        let err =
+            generate_transaction_proof_outputs(&get_state(tampered_receipt_json).unwrap(), None)
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("does NOT match the block's `transaction_mroot`"));
+        let mut tampered_id_json = get_sample_submission_json_n(1).unwrap();
+        match &mut tampered_id_json.block.transactions.as_mut().unwrap()[0].trx {
+            TransactionReceiptTrxJson::Packed(packed_transaction) => {
+                packed_transaction.id = "00".repeat(32)
+            }
+            TransactionReceiptTrxJson::Id(_) => panic!("Sample transaction should be packed!"),
+        };
+        let err = generate_transaction_proof_outputs(&get_state(tampered_id_json).unwrap(), None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not the hash of its `packed_trx`"));
+        let mut no_transactions_json = get_sample_submission_json_n(1).unwrap();
+        no_transactions_json.block.transactions = None;
+        assert!(generate_transaction_proof_outputs(
+            &get_state(no_transactions_json).unwrap(),
+            None
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::{
+    error::AppError,
+    hex_utils::{decode_checksum256, strip_hex_prefix, CHECKSUM256_LENGTH},
+    types::{BlockTimestamp, Byte, Bytes, EosBlockJson, Result},
+    varuint_utils::serialize_varuint32,
+};
+use bitcoin_hashes::{ripemd160, sha256, Hash};
+use eos_chain::{AccountName, SerializeData};
+use serde_json::Value;
+use std::str::FromStr;
+
+const PUBLIC_KEY_LENGTH: usize = 33;
+const SIGNATURE_LENGTH: usize = 65;
+const CHECKSUM_LENGTH: usize = 4;
+const LEGACY_PUBLIC_KEY_PREFIX: &str = "EOS";
+// NOTE: Offsets into a serialized header, after its timestamp, producer & `confirmed` count.
+const PREVIOUS_OFFSET: usize = 14;
+const ACTION_MROOT_OFFSET: usize = 78;
+
+fn get_invalid_field_err(field: &str, value: &impl std::fmt::Display) -> AppError {
+    AppError::Custom(format!("✘ Invalid block header `{}`: {}", field, value))
+}
+
+fn serialize_name(name: &str) -> Result<Bytes> {
+    Ok(AccountName::from_str(name)?.to_serialize_data()?)
+}
+
+fn serialize_checksum256(field: &str, hex: &str) -> Result<Bytes> {
+    decode_checksum256(hex)
+        .map(|checksum| checksum.to_vec())
+        .map_err(|_| get_invalid_field_err(field, &hex))
+}
+
+fn decode_base58(string: &str) -> Result<Bytes> {
+    bs58::decode(string)
+        .into_vec()
+        .map_err(|e| AppError::Custom(format!("✘ Invalid base58 string: {}\n✘ {}", string, e)))
+}
+
+pub fn serialize_public_key(public_key: &str) -> Result<Bytes> {
+    // NOTE: Keys serialize as their curve's variant index followed by the 33 byte compressed key.
+    let (key_type, curve_suffix, encoded) = match public_key.split_once('_') {
+        None if public_key.starts_with(LEGACY_PUBLIC_KEY_PREFIX) => {
+            (0, "", &public_key[LEGACY_PUBLIC_KEY_PREFIX.len()..])
+        }
+        Some(("PUB", rest)) => match rest.split_once('_') {
+            Some(("K1", encoded)) => (0, "K1", encoded),
+            Some(("R1", encoded)) => (1, "R1", encoded),
+            _ => return Err(get_invalid_field_err("block_signing_key", &public_key)),
+        },
+        _ => return Err(get_invalid_field_err("block_signing_key", &public_key)),
+    };
+    let bytes = decode_base58(encoded)?;
+    if bytes.len() != PUBLIC_KEY_LENGTH + CHECKSUM_LENGTH {
+        return Err(get_invalid_field_err("block_signing_key", &public_key));
+    };
+    let (key, checksum) = bytes.split_at(PUBLIC_KEY_LENGTH);
+    let expected_checksum =
+        ripemd160::Hash::hash(&[key, curve_suffix.as_bytes()].concat()).into_inner();
+    match &expected_checksum[..CHECKSUM_LENGTH] == checksum {
+        true => Ok([vec![key_type], key.to_vec()].concat()),
+        false => Err(AppError::Custom(format!(
+            "✘ Checksum mismatch in public key: {}",
+            public_key
+        ))),
+    }
+}
+
+/// Serializes a `SIG_K1_` or `SIG_R1_` signature as its curve's variant index followed by its 65
+/// bytes.
+pub fn serialize_signature(signature: &str) -> Result<Bytes> {
+    let (key_type, curve_suffix, encoded) = match signature
+        .strip_prefix("SIG_")
+        .and_then(|rest| rest.split_once('_'))
+    {
+        Some(("K1", encoded)) => (0, "K1", encoded),
+        Some(("R1", encoded)) => (1, "R1", encoded),
+        _ => {
+            return Err(AppError::Custom(format!(
+                "✘ Unsupported signature: {}",
+                signature
+            )))
+        }
+    };
+    let bytes = decode_base58(encoded)?;
+    if bytes.len() != SIGNATURE_LENGTH + CHECKSUM_LENGTH {
+        return Err(AppError::Custom(format!(
+            "✘ Invalid signature: {}",
+            signature
+        )));
+    };
+    let (signature_bytes, checksum) = bytes.split_at(SIGNATURE_LENGTH);
+    let expected_checksum =
+        ripemd160::Hash::hash(&[signature_bytes, curve_suffix.as_bytes()].concat()).into_inner();
+    match &expected_checksum[..CHECKSUM_LENGTH] == checksum {
+        true => Ok([vec![key_type], signature_bytes.to_vec()].concat()),
+        false => Err(AppError::Custom(format!(
+            "✘ Checksum mismatch in signature: {}",
+            signature
+        ))),
+    }
+}
+
+fn serialize_producer_key(producer_key: &Value) -> Result<Bytes> {
+    match (
+        producer_key["producer_name"].as_str(),
+        producer_key["block_signing_key"].as_str(),
+    ) {
+        (Some(producer_name), Some(block_signing_key)) => Ok([
+            serialize_name(producer_name)?,
+            serialize_public_key(block_signing_key)?,
+        ]
+        .concat()),
+        _ => Err(get_invalid_field_err("new_producers", producer_key)),
+    }
+}
+
+fn serialize_new_producers(new_producers: &Value) -> Result<Bytes> {
+    // NOTE: An optional legacy `producer_schedule_type`, ie a version & its producers' keys.
+    match new_producers {
+        Value::Null => Ok(vec![0]),
+        Value::Object(_) => {
+            let version = new_producers["version"]
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| get_invalid_field_err("new_producers", new_producers))?;
+            let producers = new_producers["producers"]
+                .as_array()
+                .ok_or_else(|| get_invalid_field_err("new_producers", new_producers))?;
+            Ok([
+                vec![1],
+                version.to_le_bytes().to_vec(),
+                serialize_varuint32(producers.len() as u32),
+                producers
+                    .iter()
+                    .map(serialize_producer_key)
+                    .collect::<Result<Vec<Bytes>>>()?
+                    .concat(),
+            ]
+            .concat())
+        }
+        _ => Err(get_invalid_field_err("new_producers", new_producers)),
+    }
+}
+
+fn serialize_header_extension(extension: &Value) -> Result<Bytes> {
+    // NOTE: `nodeos` gives each extension as a `[type, hex_data]` pair.
+    match (extension[0].as_u64(), extension[1].as_str()) {
+        (Some(extension_type), Some(hex_data)) if extension_type <= u16::MAX as u64 => {
+            let data = hex::decode(strip_hex_prefix(hex_data))?;
+            Ok([
+                (extension_type as u16).to_le_bytes().to_vec(),
+                serialize_varuint32(data.len() as u32),
+                data,
+            ]
+            .concat())
+        }
+        _ => Err(get_invalid_field_err("header_extensions", extension)),
+    }
+}
+
+fn serialize_header_extensions(header_extensions: &Value) -> Result<Bytes> {
+    match header_extensions {
+        Value::Null => Ok(serialize_varuint32(0)),
+        Value::Array(extensions) => Ok([
+            serialize_varuint32(extensions.len() as u32),
+            extensions
+                .iter()
+                .map(serialize_header_extension)
+                .collect::<Result<Vec<Bytes>>>()?
+                .concat(),
+        ]
+        .concat()),
+        _ => Err(get_invalid_field_err(
+            "header_extensions",
+            header_extensions,
+        )),
+    }
+}
+
+/// Serializes the block header in the EOSIO wire format, as it is hashed to form the block id.
+pub fn serialize_block_header_json(block_json: &EosBlockJson) -> Result<Bytes> {
+    let confirmed = u16::try_from(block_json.confirmed)
+        .map_err(|_| get_invalid_field_err("confirmed", &block_json.confirmed))?;
+    let schedule_version = u32::try_from(block_json.schedule_version)
+        .map_err(|_| get_invalid_field_err("schedule_version", &block_json.schedule_version))?;
+    Ok([
+        BlockTimestamp::from_str(&block_json.timestamp)?
+            .0
+            .to_le_bytes()
+            .to_vec(),
+        serialize_name(&block_json.producer)?,
+        confirmed.to_le_bytes().to_vec(),
+        serialize_checksum256("previous", &block_json.previous)?,
+        serialize_checksum256("transaction_mroot", &block_json.transaction_mroot)?,
+        serialize_checksum256("action_mroot", &block_json.action_mroot)?,
+        schedule_version.to_le_bytes().to_vec(),
+        serialize_new_producers(&block_json.new_producers)?,
+        serialize_header_extensions(&block_json.header_extensions)?,
+    ]
+    .concat())
+}
+
+pub fn get_block_header_digest(block_json: &EosBlockJson) -> Result<Bytes> {
+    serialize_block_header_json(block_json).map(|bytes| sha256::Hash::hash(&bytes).to_vec())
+}
+
+fn get_checksum256_from_serialized_block_header(
+    serialized_block_header: &[Byte],
+    offset: usize,
+) -> Result<Bytes> {
+    match serialized_block_header.get(offset..offset + CHECKSUM256_LENGTH) {
+        Some(bytes) => Ok(bytes.to_vec()),
+        None => Err(AppError::Custom(format!(
+            "✘ Serialized block header is too short: {}",
+            hex::encode(serialized_block_header)
+        ))),
+    }
+}
+
+pub fn get_action_mroot_from_serialized_block_header(
+    serialized_block_header: &[Byte],
+) -> Result<Bytes> {
+    get_checksum256_from_serialized_block_header(serialized_block_header, ACTION_MROOT_OFFSET)
+}
+
+/// Computes the block id from the serialized header: its digest with the first 4 bytes replaced
+/// by the big-endian block number, which is one more than that of the previous block.
+pub fn get_block_id_from_serialized_block_header(
+    serialized_block_header: &[Byte],
+) -> Result<Bytes> {
+    let previous =
+        get_checksum256_from_serialized_block_header(serialized_block_header, PREVIOUS_OFFSET)?;
+    let block_num =
+        u32::from_be_bytes([previous[0], previous[1], previous[2], previous[3]]).wrapping_add(1);
+    let mut block_id = sha256::Hash::hash(serialized_block_header).to_vec();
+    block_id[..4].copy_from_slice(&block_num.to_be_bytes());
+    Ok(block_id)
+}
+
+pub fn get_block_id_from_block_header_json(block_json: &EosBlockJson) -> Result<Bytes> {
+    serialize_block_header_json(block_json)
+        .and_then(|bytes| get_block_id_from_serialized_block_header(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_sample_submission_json_n;
+
+    const SAMPLE_PUBLIC_KEY_HEX: &str =
+        "02c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf";
+
+    #[test]
+    fn should_get_block_id_from_block_header_json() {
+        let block_json = get_sample_submission_json_n(1).unwrap().block;
+        let result = get_block_id_from_block_header_json(&block_json).unwrap();
+        assert_eq!(hex::encode(result), block_json.block_id);
+    }
+
+    #[test]
+    fn should_serialize_legacy_and_k1_public_keys() {
+        let expected_result = format!("00{}", SAMPLE_PUBLIC_KEY_HEX);
+        let legacy_key = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+        let k1_key = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63";
+        assert_eq!(
+            hex::encode(serialize_public_key(legacy_key).unwrap()),
+            expected_result
+        );
+        assert_eq!(
+            hex::encode(serialize_public_key(k1_key).unwrap()),
+            expected_result
+        );
+    }
+
+    #[test]
+    fn should_fail_to_serialize_public_key_with_bad_checksum() {
+        let key = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CW";
+        assert!(serialize_public_key(key).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_serialize_public_key_that_is_not_base58() {
+        let key = "EOS0MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+        let err = serialize_public_key(key).unwrap_err().to_string();
+        assert!(err.contains("Invalid base58 string"));
+    }
+
+    #[test]
+    fn should_serialize_new_producers_and_header_extensions() {
+        let new_producers = serde_json::json!({
+            "version": 2,
+            "producers": [{
+                "producer_name": "eosio",
+                "block_signing_key": "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+            }],
+        });
+        let header_extensions = serde_json::json!([[1, "abcd"]]);
+        assert_eq!(
+            hex::encode(serialize_new_producers(&new_producers).unwrap()),
+            format!("0102000000010000000000ea305500{}", SAMPLE_PUBLIC_KEY_HEX)
+        );
+        assert_eq!(
+            hex::encode(serialize_header_extensions(&header_extensions).unwrap()),
+            "01010002abcd"
+        );
+    }
+
+    #[test]
+    fn should_get_block_id_and_action_mroot_from_serialized_block_header() {
+        let block_json = get_sample_submission_json_n(1).unwrap().block;
+        let serialized_block_header = serialize_block_header_json(&block_json).unwrap();
+        assert_eq!(
+            hex::encode(
+                get_block_id_from_serialized_block_header(&serialized_block_header).unwrap()
+            ),
+            block_json.block_id
+        );
+        assert_eq!(
+            hex::encode(
+                get_action_mroot_from_serialized_block_header(&serialized_block_header).unwrap()
+            ),
+            block_json.action_mroot
+        );
+        assert!(get_block_id_from_serialized_block_header(&serialized_block_header[..40]).is_err());
+    }
+
+    #[test]
+    fn should_get_different_block_id_if_header_is_tampered_with() {
+        let mut block_json = get_sample_submission_json_n(1).unwrap().block;
+        block_json.action_mroot = "00".repeat(32);
+        let result = get_block_id_from_block_header_json(&block_json).unwrap();
+        assert_ne!(hex::encode(result), block_json.block_id);
+    }
+}
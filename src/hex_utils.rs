@@ -0,0 +1,53 @@
+use crate::{
+    error::AppError,
+    types::{Byte, Checksum256Bytes, Result},
+};
+use std::convert::TryFrom;
+
+pub const CHECKSUM256_LENGTH: usize = 32;
+
+pub fn strip_hex_prefix(hex: &str) -> &str {
+    hex.strip_prefix("0x").unwrap_or(hex)
+}
+
+pub fn convert_bytes_to_checksum256(bytes: &[Byte]) -> Result<Checksum256Bytes> {
+    Checksum256Bytes::try_from(bytes).map_err(|_| {
+        AppError::Custom(format!(
+            "✘ A checksum256 must be {} bytes, not {}!",
+            CHECKSUM256_LENGTH,
+            bytes.len()
+        ))
+    })
+}
+
+/// Decodes a hex string, with or without a `0x` prefix, which must be exactly 32 bytes.
+pub fn decode_checksum256(hex: &str) -> Result<Checksum256Bytes> {
+    hex::decode(strip_hex_prefix(hex))
+        .ok()
+        .and_then(|bytes| convert_bytes_to_checksum256(&bytes).ok())
+        .ok_or_else(|| AppError::Custom(format!("✘ Invalid checksum256: '{}'", hex)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_decode_checksum256_with_or_without_hex_prefix() {
+        let hex = "ab".repeat(CHECKSUM256_LENGTH);
+        let expected_result = [0xab; CHECKSUM256_LENGTH];
+        assert_eq!(decode_checksum256(&hex).unwrap(), expected_result);
+        assert_eq!(
+            decode_checksum256(&format!("0x{}", hex)).unwrap(),
+            expected_result
+        );
+    }
+
+    #[test]
+    fn should_fail_to_decode_checksum256_of_wrong_length() {
+        assert!(decode_checksum256("").is_err());
+        assert!(decode_checksum256(&"ab".repeat(31)).is_err());
+        assert!(decode_checksum256(&"ab".repeat(33)).is_err());
+        assert!(decode_checksum256(&"zz".repeat(32)).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    error::AppError,
+    parse_eos_block::get_block_num_from_block_id,
+    parse_input_json::put_action_receipts_and_maybe_selected_actions_in_input_json,
+    types::{
+        ActionTraceAuthSequenceJson, ActionTraceJson, AuthSequenceJson, EosActionJson,
+        EosActionJsons, EosActionReceiptJson, EosActionReceiptJsons, EosInputJson,
+        GetActionsResponseJson, NumberOrStringJson, Result,
+    },
+};
+
+pub fn parse_number_or_string_json(json: &NumberOrStringJson) -> Result<u64> {
+    match json {
+        NumberOrStringJson::Number(number) => Ok(*number),
+        NumberOrStringJson::String(string) => string.parse().map_err(|_| {
+            AppError::Custom(format!(
+                "✘ Could not parse number from string: '{}'",
+                string
+            ))
+        }),
+    }
+}
+
+fn convert_action_trace_auth_sequence_json(
+    auth_sequence: &ActionTraceAuthSequenceJson,
+) -> Result<AuthSequenceJson> {
+    match auth_sequence {
+        ActionTraceAuthSequenceJson::Tuple(account, sequence)
+        | ActionTraceAuthSequenceJson::Object { account, sequence } => Ok(AuthSequenceJson(
+            account.clone(),
+            parse_number_or_string_json(sequence)?,
+        )),
+    }
+}
+
+fn convert_action_trace_to_action_receipt_json(
+    action_trace: &ActionTraceJson,
+) -> Result<EosActionReceiptJson> {
+    let receipt = &action_trace.receipt;
+    Ok(EosActionReceiptJson {
+        tx_id: action_trace.trx_id.clone(),
+        receiver: receipt.receiver.clone(),
+        act_digest: receipt.act_digest.clone(),
+        global_sequence: parse_number_or_string_json(&receipt.global_sequence)?,
+        recv_sequence: parse_number_or_string_json(&receipt.recv_sequence)?,
+        auth_sequence: receipt
+            .auth_sequence
+            .iter()
+            .map(convert_action_trace_auth_sequence_json)
+            .collect::<Result<Vec<AuthSequenceJson>>>()?,
+        code_sequence: parse_number_or_string_json(&receipt.code_sequence)? as usize,
+        abi_sequence: parse_number_or_string_json(&receipt.abi_sequence)? as usize,
+    })
+}
+
+fn convert_action_trace_to_action_json(action_trace: &ActionTraceJson) -> EosActionJson {
+    let mut action_json = action_trace.act.clone();
+    if action_json.return_value_hex_data.is_none() {
+        action_json.return_value_hex_data = action_trace
+            .return_value_hex_data
+            .clone()
+            .filter(|return_value| !return_value.is_empty());
+    }
+    action_json
+}
+
+fn action_trace_is_in_block(action_trace: &ActionTraceJson, block_num: u64) -> Result<bool> {
+    // NOTE: Paginated `get_actions` responses often span several blocks.
+    match &action_trace.block_num {
+        Some(trace_block_num) => Ok(parse_number_or_string_json(trace_block_num)? == block_num),
+        None => Ok(true),
+    }
+}
+
+fn get_sorted_receipt_and_action_jsons_from_get_actions_response(
+    get_actions_response: &GetActionsResponseJson,
+    block_num: u64,
+) -> Result<(EosActionReceiptJsons, EosActionJsons)> {
+    let mut pairs = vec![];
+    for action in &get_actions_response.actions {
+        let action_trace = &action.action_trace;
+        if action_trace_is_in_block(action_trace, block_num)? {
+            pairs.push((
+                convert_action_trace_to_action_receipt_json(action_trace)?,
+                convert_action_trace_to_action_json(action_trace),
+            ));
+        }
+    }
+    // NOTE: An action notifying several accounts is listed once per account's history.
+    pairs.sort_by_key(|(receipt_json, _)| receipt_json.global_sequence);
+    pairs.dedup_by_key(|(receipt_json, _)| receipt_json.global_sequence);
+    Ok(pairs.into_iter().unzip())
+}
+
+pub fn get_action_receipt_jsons_from_get_actions_response(
+    get_actions_response: &GetActionsResponseJson,
+    block_num: u64,
+) -> Result<EosActionReceiptJsons> {
+    get_sorted_receipt_and_action_jsons_from_get_actions_response(get_actions_response, block_num)
+        .map(|(action_receipts, _)| action_receipts)
+}
+
+/// If the input carries a history `get_actions_response` in place of `action_receipts`, extracts
+/// the block's receipts from it. If it has no `action` or `actions` either, its `selector` picks
+/// them out.
+pub fn maybe_get_action_receipts_from_get_actions_response(
+    mut input_json: EosInputJson,
+) -> Result<EosInputJson> {
+    let get_actions_response = match input_json.get_actions_response.take() {
+        None => return Ok(input_json),
+        Some(_) if !input_json.action_receipts.is_empty() || input_json.trace_api_block.is_some() => {
+            return Err(AppError::Custom(
+                "✘ Input JSON must not contain a `get_actions_response` as well as `action_receipts` or a `trace_api_block`!"
+                    .to_string(),
+            ))
+        }
+        Some(get_actions_response) => get_actions_response,
+    };
+    info!("✔ Getting action receipts from `get_actions_response`...");
+    let block_num = get_block_num_from_block_id(&input_json.block.block_id)?;
+    let (action_receipts, action_jsons) =
+        get_sorted_receipt_and_action_jsons_from_get_actions_response(
+            &get_actions_response,
+            block_num,
+        )?;
+    put_action_receipts_and_maybe_selected_actions_in_input_json(
+        input_json,
+        action_receipts,
+        action_jsons,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+        test_utils::{get_sample_get_actions_response_json_n, get_sample_submission_json_n},
+        types::ReceiptSelectorJson,
+    };
+
+    const SAMPLE_BLOCK_NUM: u64 = 84756340;
+
+    fn get_sample_input_json_with_get_actions_response() -> EosInputJson {
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        input_json.action_receipts = vec![];
+        input_json.get_actions_response = Some(get_sample_get_actions_response_json_n(1).unwrap());
+        input_json
+    }
+
+    #[test]
+    fn should_parse_numbers_and_numeric_strings() {
+        let number = NumberOrStringJson::Number(1337);
+        let string = NumberOrStringJson::String("1337".to_string());
+        assert_eq!(parse_number_or_string_json(&number).unwrap(), 1337);
+        assert_eq!(parse_number_or_string_json(&string).unwrap(), 1337);
+    }
+
+    #[test]
+    fn should_fail_to_parse_non_numeric_string() {
+        let string = NumberOrStringJson::String("0x539".to_string());
+        assert!(parse_number_or_string_json(&string).is_err());
+    }
+
+    #[test]
+    fn should_get_normalised_action_receipt_jsons_from_get_actions_response() {
+        // NOTE: The sample mixes in Hyperion-style traces, a duplicate & a trace from the next block.
+        let get_actions_response = get_sample_get_actions_response_json_n(1).unwrap();
+        assert_eq!(get_actions_response.actions.len(), 8);
+        let result = get_action_receipt_jsons_from_get_actions_response(
+            &get_actions_response,
+            SAMPLE_BLOCK_NUM,
+        )
+        .unwrap();
+        let expected_result = sort_action_receipt_jsons_by_global_sequence(
+            &get_sample_submission_json_n(1).unwrap().action_receipts,
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::to_value(&expected_result).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_get_action_receipts_and_selected_action_from_get_actions_response_in_input_json() {
+        let expected_result = get_sample_submission_json_n(1).unwrap();
+        let mut input_json = get_sample_input_json_with_get_actions_response();
+        input_json.action = None;
+        input_json.selector = Some(ReceiptSelectorJson {
+            global_sequence: Some(584628427),
+            ..Default::default()
+        });
+        let result = maybe_get_action_receipts_from_get_actions_response(input_json).unwrap();
+        assert!(result.get_actions_response.is_none());
+        assert_eq!(result.action_receipts.len(), 6);
+        assert_eq!(
+            serde_json::to_value(&result.action).unwrap(),
+            serde_json::to_value(&expected_result.action).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_fail_if_input_json_has_get_actions_response_and_action_receipts() {
+        let mut input_json = get_sample_input_json_with_get_actions_response();
+        input_json.action_receipts = get_sample_submission_json_n(1).unwrap().action_receipts;
+        assert!(maybe_get_action_receipts_from_get_actions_response(input_json).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    eos_merkle_utils::{make_and_hash_canonical_pair, make_canonical_left, make_canonical_right},
+    error::AppError,
+    hex_utils::{decode_checksum256, CHECKSUM256_LENGTH},
+    types::{Checksum256Bytes, IncrementalMerkleJson, MerkleProof, Result},
+};
+
+/// An EOSIO incremental merkle, as kept in a block header state's `blockroot_merkle`. It holds
+/// only the nodes needed to append further leaves, yet has the same root as a merkle tree built
+/// over every leaf appended so far.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IncrementalMerkle {
+    node_count: u64,
+    active_nodes: Vec<Checksum256Bytes>,
+}
+
+fn get_max_depth(node_count: u64) -> usize {
+    // NOTE: The depth of a tree with the node count rounded up to the next power of two.
+    match node_count {
+        0 => 0,
+        _ => node_count.next_power_of_two().trailing_zeros() as usize + 1,
+    }
+}
+
+fn get_num_active_nodes(node_count: u64) -> usize {
+    // NOTE: The root of each complete left subtree, plus the overall root if it isn't one itself.
+    match node_count {
+        0 => 0,
+        _ if node_count.is_power_of_two() => 1,
+        _ => node_count.count_ones() as usize + 1,
+    }
+}
+
+fn decode_node(node: &str) -> Result<Checksum256Bytes> {
+    decode_checksum256(node)
+        .map_err(|_| AppError::Custom(format!("✘ Invalid incremental merkle node: {}", node)))
+}
+
+fn get_subtree_root(leaves: &[Checksum256Bytes], depth: usize) -> Checksum256Bytes {
+    // NOTE: Any odd last node pairs with itself, as though the subtree were full.
+    (0..depth)
+        .fold(leaves.to_vec(), |level, _| {
+            level
+                .chunks(2)
+                .map(|pair| make_and_hash_canonical_pair(pair[0], pair[pair.len() - 1]))
+                .collect::<Vec<Checksum256Bytes>>()
+        })
+        .remove(0)
+}
+
+impl IncrementalMerkle {
+    pub fn new(node_count: u64, active_nodes: Vec<Checksum256Bytes>) -> Result<Self> {
+        let expected_num_active_nodes = get_num_active_nodes(node_count);
+        match active_nodes.len() == expected_num_active_nodes {
+            true => Ok(IncrementalMerkle {
+                node_count,
+                active_nodes,
+            }),
+            false => Err(AppError::Custom(format!(
+                "✘ An incremental merkle of {} nodes needs {} active nodes, not {}!",
+                node_count,
+                expected_num_active_nodes,
+                active_nodes.len()
+            ))),
+        }
+    }
+
+    pub fn from_json(json: &IncrementalMerkleJson) -> Result<Self> {
+        json.active_nodes
+            .iter()
+            .map(|node| decode_node(node))
+            .collect::<Result<Vec<Checksum256Bytes>>>()
+            .and_then(|active_nodes| Self::new(json.node_count, active_nodes))
+    }
+
+    pub fn to_json(&self) -> IncrementalMerkleJson {
+        IncrementalMerkleJson {
+            node_count: self.node_count,
+            active_nodes: self.active_nodes.iter().map(hex::encode).collect(),
+        }
+    }
+
+    pub fn get_node_count(&self) -> u64 {
+        self.node_count
+    }
+
+    pub fn get_root(&self) -> Checksum256Bytes {
+        match self.active_nodes.last() {
+            Some(root) => *root,
+            None => [0; CHECKSUM256_LENGTH],
+        }
+    }
+
+    /// Appends a leaf, collapsing it with the active nodes to its left, as `nodeos` does.
+    pub fn append(&mut self, leaf: Checksum256Bytes) -> Checksum256Bytes {
+        let mut index = self.node_count;
+        let mut is_partial = false;
+        let mut node = leaf;
+        let mut left_nodes = std::mem::take(&mut self.active_nodes).into_iter();
+        let mut active_nodes = vec![];
+        for _ in 1..get_max_depth(self.node_count + 1) {
+            match index & 1 {
+                0 => {
+                    // NOTE: A left node without its right yet, so only kept if fully realized.
+                    if !is_partial {
+                        active_nodes.push(node);
+                    };
+                    node = make_and_hash_canonical_pair(node, node);
+                    is_partial = true;
+                }
+                _ => {
+                    let left_node = left_nodes.next().unwrap_or_default();
+                    if is_partial {
+                        active_nodes.push(left_node);
+                    };
+                    node = make_and_hash_canonical_pair(left_node, node);
+                }
+            }
+            index >>= 1;
+        }
+        active_nodes.push(node);
+        self.active_nodes = active_nodes;
+        self.node_count += 1;
+        self.get_root()
+    }
+
+    /// Generates a merkle proof that the first of the `leaves`, appended next, is committed to by
+    /// the root this merkle has once all of the `leaves` have been appended.
+    pub fn generate_proof(&self, leaves: &[Checksum256Bytes]) -> Result<MerkleProof> {
+        if leaves.is_empty() {
+            return Err(AppError::Custom(
+                "✘ Need at least one leaf to generate an incremental merkle proof!".to_string(),
+            ));
+        };
+        let index = self.node_count;
+        let num_leaves = self.node_count + leaves.len() as u64;
+        // NOTE: The active nodes are the left siblings of the next leaf, from the bottom up.
+        let mut left_siblings = self.active_nodes.iter();
+        let mut node = leaves[0];
+        let mut proof = vec![hex::encode(node)];
+        for depth in 0..get_max_depth(num_leaves).saturating_sub(1) {
+            if (index >> depth) & 1 == 1 {
+                let sibling =
+                    make_canonical_left(left_siblings.next().cloned().ok_or_else(|| {
+                        AppError::Custom(
+                            "✘ Incremental merkle is missing an active node!".to_string(),
+                        )
+                    })?);
+                proof.push(hex::encode(sibling));
+                node = make_and_hash_canonical_pair(sibling, node);
+            } else {
+                let start = ((((index >> depth) + 1) << depth) - index) as usize;
+                let sibling = match start < leaves.len() {
+                    true => {
+                        let end = std::cmp::min(start + (1 << depth), leaves.len());
+                        make_canonical_right(get_subtree_root(&leaves[start..end], depth))
+                    }
+                    false => make_canonical_right(node),
+                };
+                proof.push(hex::encode(sibling));
+                node = make_and_hash_canonical_pair(node, sibling);
+            };
+        }
+        let mut incremental_merkle = self.clone();
+        leaves.iter().for_each(|leaf| {
+            incremental_merkle.append(*leaf);
+        });
+        match node == incremental_merkle.get_root() {
+            true => {
+                proof.push(hex::encode(node));
+                Ok(proof)
+            }
+            false => Err(AppError::Custom(
+                "✘ Incremental merkle proof does NOT hash up to the appended root!".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eos_merkle_utils::{
+        generate_merkle_proof, get_merkle_root_from_tree, get_merkle_tree, verify_merkle_proof,
+        MerkleTree,
+    };
+    use bitcoin_hashes::{sha256, Hash};
+
+    fn get_leaves(num_leaves: usize) -> Vec<Checksum256Bytes> {
+        (0..num_leaves)
+            .map(|i| sha256::Hash::hash(&i.to_le_bytes()).into_inner())
+            .collect()
+    }
+
+    fn get_full_merkle_tree(leaves: &[Checksum256Bytes]) -> MerkleTree {
+        get_merkle_tree(leaves.iter().map(|leaf| leaf.to_vec()).collect()).unwrap()
+    }
+
+    fn get_incremental_merkle(leaves: &[Checksum256Bytes]) -> IncrementalMerkle {
+        let mut incremental_merkle = IncrementalMerkle::default();
+        leaves.iter().for_each(|leaf| {
+            incremental_merkle.append(*leaf);
+        });
+        incremental_merkle
+    }
+
+    #[test]
+    fn should_get_same_root_as_full_merkle_tree() {
+        (1..40).for_each(|num_leaves| {
+            let leaves = get_leaves(num_leaves);
+            let incremental_merkle = get_incremental_merkle(&leaves);
+            assert_eq!(
+                incremental_merkle.get_root(),
+                get_merkle_root_from_tree(&get_full_merkle_tree(&leaves))
+            );
+            assert_eq!(
+                incremental_merkle.active_nodes.len(),
+                get_num_active_nodes(num_leaves as u64)
+            );
+        });
+    }
+
+    #[test]
+    fn should_generate_same_proofs_as_full_merkle_tree() {
+        (1..24).for_each(|num_leaves| {
+            let leaves = get_leaves(num_leaves);
+            let tree = get_full_merkle_tree(&leaves);
+            let root = get_merkle_root_from_tree(&tree);
+            (0..num_leaves).for_each(|index| {
+                let proof = get_incremental_merkle(&leaves[..index])
+                    .generate_proof(&leaves[index..])
+                    .unwrap();
+                assert_eq!(proof, generate_merkle_proof(index, &tree).unwrap());
+                assert!(verify_merkle_proof(&proof, &root).unwrap());
+            });
+        });
+    }
+
+    #[test]
+    fn should_round_trip_incremental_merkle_json() {
+        let incremental_merkle = get_incremental_merkle(&get_leaves(11));
+        let json = incremental_merkle.to_json();
+        assert_eq!(json.node_count, 11);
+        assert_eq!(
+            IncrementalMerkle::from_json(&json).unwrap(),
+            incremental_merkle
+        );
+    }
+
+    #[test]
+    fn should_fail_to_make_incremental_merkle_with_wrong_number_of_active_nodes() {
+        let mut json = get_incremental_merkle(&get_leaves(11)).to_json();
+        json.active_nodes.pop();
+        assert!(IncrementalMerkle::from_json(&json).is_err());
+        assert!(IncrementalMerkle::default().generate_proof(&[]).is_err());
+    }
+}
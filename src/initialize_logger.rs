@@ -1,5 +1,6 @@
-use crate::{constants::LOG_FILE_PATH, error::AppError, state::State, types::Result};
+use crate::{constants::LOG_FILE_PATH, parse_cli_args::CliArgs};
 use chrono::Utc;
+use eos_action_proof_maker::{error::AppError, types::Result};
 use log::LevelFilter;
 use simplelog::*;
 use std::{
@@ -11,7 +12,7 @@ fn get_log_file_path() -> String {
     format!("{}{}.log", LOG_FILE_PATH, Utc::now())
 }
 
-pub fn initialize_logger(state: State) -> Result<State> {
+pub fn initialize_logger(cli_args: CliArgs) -> Result<CliArgs> {
     let log_path = get_log_file_path();
     if !Path::new(&LOG_FILE_PATH).exists() {
         info!("✔ No log dir found, creating...");
@@ -25,7 +26,7 @@ pub fn initialize_logger(state: State) -> Result<State> {
         Ok(_) => {
             info!("✔ Logger initialized successfully");
             info!("✔ Log writing to: {}", log_path);
-            Ok(state)
+            Ok(cli_args)
         }
         Err(e) => Err(AppError::Custom(e.to_string())),
     }
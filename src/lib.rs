@@ -0,0 +1,45 @@
+pub mod eos_merkle_utils;
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod get_action_digest;
+pub mod history_api;
+pub mod incremental_merkle;
+pub mod proof_maker;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod read_input_file;
+pub mod state_history;
+pub mod trace_api;
+pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+mod block_timestamp;
+mod build_merkle_tree;
+mod find_index_of_action;
+mod generate_block_proof;
+mod generate_multi_proof;
+mod generate_output;
+mod generate_proof;
+mod generate_transaction_proof;
+mod get_block_id;
+mod hex_utils;
+mod parse_eos_action;
+mod parse_eos_action_receipts;
+mod parse_eos_block;
+mod parse_input_json;
+mod state;
+mod test_utils;
+mod validate_action_mroot;
+mod validate_block_id;
+mod validate_producer_signature;
+mod varuint_utils;
+mod verify_output;
+mod verify_proof;
+
+pub use crate::proof_maker::{GenerateOptions, ProofMaker};
+
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate serde_derive;
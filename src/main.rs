@@ -1,23 +1,9 @@
-mod action_return_values;
 mod constants;
-mod eos_merkle_utils;
-mod error;
-mod find_index_of_action;
-mod generate_output;
-mod generate_proof;
-mod get_action_digest;
+mod fetch_input_json;
 mod initialize_logger;
 mod parse_cli_args;
-mod parse_eos_action;
-mod parse_eos_action_receipts;
-mod parse_eos_block;
-mod parse_input_json;
-mod state;
-mod test_utils;
-mod types;
+mod server;
 mod usage_info;
-mod validate_action_mroot;
-mod verify_proof;
 
 #[macro_use]
 extern crate log;
@@ -25,30 +11,65 @@ extern crate log;
 extern crate serde_derive;
 
 use crate::{
-    find_index_of_action::find_index_of_action_and_put_in_state,
-    generate_output::generate_output_string, generate_proof::generate_proof_and_add_to_state,
-    initialize_logger::initialize_logger, parse_cli_args::parse_cli_args_and_put_in_state,
-    parse_eos_action::parse_eos_action_json_and_put_in_state,
-    parse_eos_action_receipts::parse_eos_action_receipt_jsons_and_put_in_state,
-    parse_eos_block::parse_eos_block_json_and_put_in_state,
-    parse_input_json::parse_input_json_string_and_put_in_state, types::Result,
-    validate_action_mroot::validate_action_receipt_merkle_root,
-    verify_proof::verify_proof_in_state,
+    fetch_input_json::fetch_input_json_and_put_in_cli_args,
+    initialize_logger::initialize_logger,
+    parse_cli_args::{
+        get_trusted_action_mroot, get_trusted_blockroot, parse_cli_args_and_read_input_json,
+        CliArgs,
+    },
+    server::bind_and_serve,
 };
+use eos_action_proof_maker::{types::Result, GenerateOptions, ProofMaker};
+
+fn generate(cli_args: CliArgs) -> Result<String> {
+    ProofMaker::generate_json_string(
+        &cli_args.arg_JSON,
+        GenerateOptions {
+            all: cli_args.flag_all,
+            multi_proof: cli_args.flag_multi_proof,
+        },
+    )
+}
+
+fn generate_tx_proof(cli_args: CliArgs) -> Result<String> {
+    ProofMaker::generate_transaction_proof_json_string(
+        &cli_args.arg_JSON,
+        match cli_args.flag_tx_id.is_empty() {
+            true => None,
+            false => Some(&cli_args.flag_tx_id),
+        },
+    )
+}
+
+fn verify(cli_args: CliArgs) -> Result<String> {
+    match cli_args.flag_blockroot.is_empty() {
+        true => ProofMaker::verify_json_string(
+            &cli_args.arg_JSON,
+            &get_trusted_action_mroot(&cli_args)?,
+        ),
+        false => ProofMaker::verify_json_string_against_blockroot(
+            &cli_args.arg_JSON,
+            &get_trusted_blockroot(&cli_args)?,
+        ),
+    }
+}
 
 fn main() -> Result<()> {
-    match parse_cli_args_and_put_in_state()
+    match parse_cli_args_and_read_input_json()
         .and_then(initialize_logger)
-        .and_then(parse_input_json_string_and_put_in_state)
-        .and_then(parse_eos_block_json_and_put_in_state)
-        .and_then(parse_eos_action_json_and_put_in_state)
-        .and_then(parse_eos_action_receipt_jsons_and_put_in_state)
-        .and_then(find_index_of_action_and_put_in_state)
-        .and_then(validate_action_receipt_merkle_root)
-        .and_then(generate_proof_and_add_to_state)
-        .and_then(verify_proof_in_state)
-        .and_then(generate_output_string)
-    {
+        .and_then(|cli_args| {
+            if cli_args.cmd_serve {
+                bind_and_serve(&cli_args.flag_bind)
+            } else if cli_args.cmd_fetch {
+                fetch_input_json_and_put_in_cli_args(cli_args).and_then(generate)
+            } else if cli_args.cmd_generate_tx_proof {
+                generate_tx_proof(cli_args)
+            } else if cli_args.cmd_verify {
+                verify(cli_args)
+            } else {
+                generate(cli_args)
+            }
+        }) {
         Ok(output) => {
             trace!("{}", output);
             println!("{}", output);
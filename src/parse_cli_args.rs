@@ -1,13 +1,42 @@
-use crate::{error::AppError, state::State, types::Result, usage_info::USAGE_INFO};
+use crate::usage_info::USAGE_INFO;
 use docopt::Docopt;
-use std::{fs::read_to_string, path::Path};
+use eos_action_proof_maker::{
+    error::AppError,
+    read_input_file::{read_file_to_bytes, read_file_to_string, read_reader_to_string},
+    types::{Bytes, EosBlockJson, Result},
+    ProofMaker,
+};
+use std::io::stdin;
+
+// NOTE: As is the convention, passing `-` as the file path means reading from stdin instead.
+const STDIN_FILE_PATH: &str = "-";
 
 #[allow(non_snake_case)]
-#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
 pub struct CliArgs {
     pub arg_JSON: String,
+    pub flag_all: bool,
     pub flag_file: String,
+    pub flag_block: String,
+    pub cmd_verify: bool,
     pub cmd_generate: bool,
+    pub cmd_generate_tx_proof: bool,
+    pub flag_action_mroot: String,
+    pub flag_multi_proof: bool,
+    pub cmd_serve: bool,
+    pub flag_bind: String,
+    pub cmd_fetch: bool,
+    pub flag_rpc_url: String,
+    pub flag_block_num: String,
+    pub flag_tx_id: String,
+    pub flag_selector: String,
+    pub flag_trace_api_block: String,
+    pub flag_get_actions: String,
+    pub flag_state_history_result: String,
+    pub flag_schedule: String,
+    pub flag_block_header_state: String,
+    pub flag_later_block_ids: String,
+    pub flag_blockroot: String,
 }
 
 impl CliArgs {
@@ -25,32 +54,230 @@ pub fn parse_cli_args() -> Result<CliArgs> {
 }
 
 pub fn maybe_read_block_json_from_file(cli_args: CliArgs) -> Result<CliArgs> {
-    match Path::new(&cli_args.flag_file).exists() {
-        true => {
-            info!(
-                "✔ File exists @ path: {},\n✔ Reading file...",
-                cli_args.flag_file,
-            );
-            cli_args
-                .clone()
-                .update_json_in_cli_args(read_to_string(cli_args.flag_file)?)
-        }
-        false => {
-            info!(
-                "✔ No file exists @ path: {}\n✔ Not reading file...",
-                cli_args.flag_file,
-            );
+    match cli_args.flag_file.as_str() {
+        "" => {
+            info!("✔ No file path supplied ∴ using the inline JSON...");
             Ok(cli_args)
         }
+        STDIN_FILE_PATH => {
+            info!("✔ Reading input JSON from stdin...");
+            cli_args.update_json_in_cli_args(read_reader_to_string(stdin().lock())?)
+        }
+        path => {
+            let json_string = read_file_to_string(path)?;
+            cli_args.update_json_in_cli_args(json_string)
+        }
+    }
+}
+
+fn maybe_add_json_file_to_input_json(cli_args: CliArgs, path: &str, key: &str) -> Result<CliArgs> {
+    match path {
+        "" => Ok(cli_args),
+        path => {
+            info!("✔ Adding `{}` to input JSON...", key);
+            let mut input_json: serde_json::Value = serde_json::from_str(&cli_args.arg_JSON)?;
+            input_json[key] = serde_json::from_str(&read_file_to_string(path)?)?;
+            let input_json_string = input_json.to_string();
+            cli_args.update_json_in_cli_args(input_json_string)
+        }
+    }
+}
+
+pub fn maybe_add_trace_api_block_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    let path = cli_args.flag_trace_api_block.clone();
+    maybe_add_json_file_to_input_json(cli_args, &path, "trace_api_block")
+}
+
+pub fn maybe_add_get_actions_response_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    let path = cli_args.flag_get_actions.clone();
+    maybe_add_json_file_to_input_json(cli_args, &path, "get_actions_response")
+}
+
+pub fn maybe_add_state_history_result_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    match cli_args.flag_state_history_result.as_str() {
+        "" => Ok(cli_args),
+        path => {
+            info!("✔ Adding `state_history_result` to input JSON...");
+            let mut input_json: serde_json::Value = serde_json::from_str(&cli_args.arg_JSON)?;
+            input_json["state_history_result"] = hex::encode(read_file_to_bytes(path)?).into();
+            let input_json_string = input_json.to_string();
+            cli_args.update_json_in_cli_args(input_json_string)
+        }
+    }
+}
+
+pub fn maybe_add_producer_schedule_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    match cli_args.flag_schedule.as_str() {
+        "" => Ok(cli_args),
+        path => {
+            info!("✔ Adding `producer_schedule` to input JSON...");
+            let mut input_json: serde_json::Value = serde_json::from_str(&cli_args.arg_JSON)?;
+            let mut producer_schedule: serde_json::Value =
+                serde_json::from_str(&read_file_to_string(path)?)?;
+            // NOTE: A whole `get_producer_schedule` response may be given, so use its `active` one.
+            if producer_schedule["active"].is_object() {
+                producer_schedule = producer_schedule["active"].take();
+            };
+            input_json["producer_schedule"] = producer_schedule;
+            let input_json_string = input_json.to_string();
+            cli_args.update_json_in_cli_args(input_json_string)
+        }
     }
 }
 
-pub fn put_cli_args_in_state(cli_args: CliArgs) -> Result<State> {
-    State::init(cli_args)
+pub fn maybe_add_block_header_state_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    let path = cli_args.flag_block_header_state.clone();
+    maybe_add_json_file_to_input_json(cli_args, &path, "block_header_state")
+}
+
+pub fn maybe_add_later_block_ids_to_input_json(cli_args: CliArgs) -> Result<CliArgs> {
+    let path = cli_args.flag_later_block_ids.clone();
+    maybe_add_json_file_to_input_json(cli_args, &path, "later_block_ids")
+}
+
+pub fn get_trusted_blockroot(cli_args: &CliArgs) -> Result<Bytes> {
+    Ok(hex::decode(
+        cli_args.flag_blockroot.trim_start_matches("0x"),
+    )?)
+}
+
+pub fn get_trusted_action_mroot(cli_args: &CliArgs) -> Result<Bytes> {
+    match cli_args.flag_action_mroot.is_empty() {
+        false => Ok(hex::decode(
+            cli_args.flag_action_mroot.trim_start_matches("0x"),
+        )?),
+        true => {
+            info!("✔ Reading trusted block header...");
+            let block_json: EosBlockJson =
+                serde_json::from_str(&read_file_to_string(&cli_args.flag_block)?)?;
+            ProofMaker::get_action_mroot_from_trusted_block(&block_json)
+        }
+    }
 }
 
-pub fn parse_cli_args_and_put_in_state() -> Result<State> {
+pub fn parse_cli_args_and_read_input_json() -> Result<CliArgs> {
     parse_cli_args()
         .and_then(maybe_read_block_json_from_file)
-        .and_then(put_cli_args_in_state)
+        .and_then(maybe_add_trace_api_block_to_input_json)
+        .and_then(maybe_add_get_actions_response_to_input_json)
+        .and_then(maybe_add_state_history_result_to_input_json)
+        .and_then(maybe_add_producer_schedule_to_input_json)
+        .and_then(maybe_add_block_header_state_to_input_json)
+        .and_then(maybe_add_later_block_ids_to_input_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_fail_to_read_block_json_from_missing_file() {
+        let path = "src/test_utils/no-such-file.json";
+        let cli_args = CliArgs {
+            flag_file: path.to_string(),
+            ..Default::default()
+        };
+        let err = maybe_read_block_json_from_file(cli_args)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(path));
+    }
+
+    #[test]
+    fn should_add_trace_api_block_to_input_json() {
+        let cli_args = CliArgs {
+            arg_JSON: "{\"block\":{}}".to_string(),
+            flag_trace_api_block: "src/test_utils/sample-trace-api-block-1.json".to_string(),
+            ..Default::default()
+        };
+        let result = maybe_add_trace_api_block_to_input_json(cli_args).unwrap();
+        let input_json: serde_json::Value = serde_json::from_str(&result.arg_JSON).unwrap();
+        assert_eq!(input_json["trace_api_block"]["number"], 84756340);
+        assert!(input_json["block"].is_object());
+    }
+
+    #[test]
+    fn should_add_get_actions_response_to_input_json() {
+        let cli_args = CliArgs {
+            arg_JSON: "{\"block\":{}}".to_string(),
+            flag_get_actions: "src/test_utils/sample-get-actions-1.json".to_string(),
+            ..Default::default()
+        };
+        let result = maybe_add_get_actions_response_to_input_json(cli_args).unwrap();
+        let input_json: serde_json::Value = serde_json::from_str(&result.arg_JSON).unwrap();
+        assert_eq!(
+            input_json["get_actions_response"]["actions"][0]["block_num"],
+            84756340
+        );
+    }
+
+    #[test]
+    fn should_add_hex_encoded_state_history_result_to_input_json() {
+        let path = "src/test_utils/sample-state-history-result-1.bin";
+        let cli_args = CliArgs {
+            arg_JSON: "{\"block\":{}}".to_string(),
+            flag_state_history_result: path.to_string(),
+            ..Default::default()
+        };
+        let result = maybe_add_state_history_result_to_input_json(cli_args).unwrap();
+        let input_json: serde_json::Value = serde_json::from_str(&result.arg_JSON).unwrap();
+        assert_eq!(
+            input_json["state_history_result"],
+            hex::encode(std::fs::read(path).unwrap())
+        );
+    }
+
+    #[test]
+    fn should_add_active_producer_schedule_and_block_header_state_to_input_json() {
+        let cli_args = CliArgs {
+            arg_JSON: "{\"block\":{}}".to_string(),
+            flag_schedule: "src/test_utils/sample-producer-schedule-1.json".to_string(),
+            flag_block_header_state: "src/test_utils/sample-block-header-state-1.json".to_string(),
+            ..Default::default()
+        };
+        let result = maybe_add_producer_schedule_to_input_json(cli_args)
+            .and_then(maybe_add_block_header_state_to_input_json)
+            .unwrap();
+        let input_json: serde_json::Value = serde_json::from_str(&result.arg_JSON).unwrap();
+        assert_eq!(input_json["producer_schedule"]["version"], 389);
+        assert_eq!(
+            input_json["block_header_state"]["blockroot_merkle"]["_node_count"],
+            84756339
+        );
+    }
+
+    #[test]
+    fn should_add_later_block_ids_to_input_json() {
+        let cli_args = CliArgs {
+            arg_JSON: "{\"block\":{}}".to_string(),
+            flag_later_block_ids: "src/test_utils/sample-later-block-ids-1.json".to_string(),
+            ..Default::default()
+        };
+        let result = maybe_add_later_block_ids_to_input_json(cli_args).unwrap();
+        let input_json: serde_json::Value = serde_json::from_str(&result.arg_JSON).unwrap();
+        assert_eq!(input_json["later_block_ids"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn should_get_trusted_action_mroot_from_validated_block_file() {
+        let cli_args = CliArgs {
+            flag_block: "src/test_utils/sample-block-1.json".to_string(),
+            ..Default::default()
+        };
+        let result = get_trusted_action_mroot(&cli_args).unwrap();
+        assert_eq!(
+            hex::encode(result),
+            "7cc717a7e256683ab4d01c05040fc503f2436625f5ac9f639a2fd0b201231564"
+        );
+    }
+
+    #[test]
+    fn should_not_read_file_if_no_path_supplied() {
+        let cli_args = CliArgs {
+            arg_JSON: "{}".to_string(),
+            ..Default::default()
+        };
+        let result = maybe_read_block_json_from_file(cli_args.clone()).unwrap();
+        assert_eq!(result, cli_args);
+    }
 }
@@ -1,7 +1,12 @@
 use crate::{
     error::AppError,
+    hex_utils::strip_hex_prefix,
+    parse_input_json::get_action_jsons_from_input_json,
     state::State,
-    types::{AuthorizationJson, AuthorizationJsons, Bytes, EosActionJson, Result},
+    types::{
+        AuthorizationJson, AuthorizationJsons, Bytes, EosActionJson, EosActionJsons, EosActions,
+        Result,
+    },
 };
 use eos_chain::{AccountName, Action as EosAction, ActionName, PermissionLevel};
 use std::str::FromStr;
@@ -49,24 +54,77 @@ pub fn parse_eos_action_json(action_json: &EosActionJson) -> Result<EosAction> {
     })
 }
 
-pub fn parse_eos_action_json_and_put_in_state(state: State) -> Result<State> {
-    trace!("✔ Parsing EOS action...");
+pub fn parse_action_return_value_json(action_json: &EosActionJson) -> Result<Bytes> {
+    match &action_json.return_value_hex_data {
+        Some(hex) => Ok(hex::decode(strip_hex_prefix(hex))?),
+        None => Ok(vec![]),
+    }
+}
+
+pub fn parse_action_return_value_jsons(action_jsons: &EosActionJsons) -> Result<Vec<Bytes>> {
+    action_jsons
+        .iter()
+        .map(parse_action_return_value_json)
+        .collect()
+}
+
+pub fn parse_eos_action_jsons(action_jsons: &EosActionJsons) -> Result<EosActions> {
+    action_jsons.iter().map(parse_eos_action_json).collect()
+}
+
+pub fn parse_eos_action_jsons_and_put_in_state(state: State) -> Result<State> {
+    trace!("✔ Parsing EOS actions...");
     state
         .get_eos_input_json()
-        .and_then(|json| parse_eos_action_json(&json.action))
-        .and_then(|action| state.add_eos_action(action))
+        .and_then(get_action_jsons_from_input_json)
+        .and_then(|action_jsons| {
+            let actions = parse_eos_action_jsons(&action_jsons)?;
+            let action_return_values = parse_action_return_value_jsons(&action_jsons)?;
+            state
+                .add_eos_actions(actions)
+                .and_then(|state| state.add_action_return_values(action_return_values))
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::get_sample_submission_json_n;
+    use crate::{
+        parse_input_json::parse_eos_input_json_string,
+        test_utils::{get_sample_batch_submission_string_n, get_sample_submission_json_n},
+    };
 
     #[test]
     fn should_parse_eos_action_json() {
-        let action_json = get_sample_submission_json_n(1).unwrap().action;
+        let action_json = get_sample_submission_json_n(1).unwrap().action.unwrap();
         if let Err(e) = parse_eos_action_json(&action_json) {
             panic!("Error parsing eos actions: {}", e);
         }
     }
+
+    #[test]
+    fn should_parse_eos_action_jsons() {
+        let action_jsons = get_sample_batch_submission_string_n(1)
+            .and_then(|string| parse_eos_input_json_string(&string))
+            .unwrap()
+            .actions
+            .unwrap();
+        let result = parse_eos_action_jsons(&action_jsons).unwrap();
+        assert_eq!(result.len(), action_jsons.len());
+    }
+
+    #[test]
+    fn should_parse_empty_action_return_value_if_none_supplied() {
+        let action_json = get_sample_submission_json_n(1).unwrap().action.unwrap();
+        let result = parse_action_return_value_json(&action_json).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn should_parse_action_return_value_json() {
+        let mut action_json = get_sample_submission_json_n(1).unwrap().action.unwrap();
+        action_json.return_value_hex_data = Some("0x0100000000000000".to_string());
+        let result = parse_action_return_value_json(&action_json).unwrap();
+        assert_eq!(result, vec![1, 0, 0, 0, 0, 0, 0, 0]);
+    }
 }
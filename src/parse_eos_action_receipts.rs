@@ -1,4 +1,5 @@
 use crate::{
+    hex_utils::decode_checksum256,
     state::State,
     types::{
         AuthSequenceJson, AuthSequenceJsons, EosActionReceiptJson, EosActionReceiptJsons, Result,
@@ -18,11 +19,12 @@ pub fn sort_action_receipts_by_global_sequence(
     sorted
 }
 
-pub fn convert_hex_to_checksum256(hex: &String) -> Result<Checksum256> {
-    let mut arr = [0; 32];
-    let bytes = hex::decode(hex)?;
-    arr.copy_from_slice(&bytes);
-    Ok(Checksum256::from(arr))
+pub fn sort_action_receipt_jsons_by_global_sequence(
+    action_receipt_jsons: &EosActionReceiptJsons,
+) -> EosActionReceiptJsons {
+    let mut sorted = action_receipt_jsons.clone();
+    sorted.sort_by_key(|receipt_json| receipt_json.global_sequence);
+    sorted
 }
 
 fn parse_auth_sequence_json(auth_sequence_json: &AuthSequenceJson) -> Result<(AccountName, u64)> {
@@ -43,14 +45,14 @@ fn parse_auth_sequence_jsons(
     ))
 }
 
-fn parse_eos_action_receipt_json(
+pub fn parse_eos_action_receipt_json(
     eos_action_receipt_json: &EosActionReceiptJson,
 ) -> Result<EosActionReceipt> {
     Ok(EosActionReceipt {
         abi_sequence: UnsignedInt::from(eos_action_receipt_json.abi_sequence),
         code_sequence: UnsignedInt::from(eos_action_receipt_json.code_sequence),
         receiver: AccountName::from_str(&eos_action_receipt_json.receiver)?,
-        act_digest: convert_hex_to_checksum256(&eos_action_receipt_json.act_digest)?,
+        act_digest: Checksum256::from(decode_checksum256(&eos_action_receipt_json.act_digest)?),
         global_sequence: eos_action_receipt_json.global_sequence,
         recv_sequence: eos_action_receipt_json.recv_sequence,
         auth_sequence: parse_auth_sequence_jsons(&eos_action_receipt_json.auth_sequence)?,
@@ -76,10 +78,8 @@ pub fn parse_eos_action_receipt_jsons_and_put_in_state(state: State) -> Result<S
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        test_utils::get_sample_submission_json_n,
-        validate_action_mroot::get_merkle_digest_from_action_receipts,
-    };
+    use crate::test_utils::{get_merkle_digest_from_action_receipts, get_sample_submission_json_n};
+    use eos_chain::Digest;
 
     #[test]
     fn should_parse_action_receipt_jsons() {
@@ -105,4 +105,23 @@ mod tests {
         assert_eq!(digest_after_sorting, expected_result_before_sort);
         assert_ne!(digest_after_sorting, digest_before_sorting);
     }
+
+    #[test]
+    fn should_parse_prefixed_act_digest_but_fail_to_parse_short_one() {
+        let receipt_json = get_sample_submission_json_n(1).unwrap().action_receipts[0].clone();
+        let expected_result = parse_eos_action_receipt_json(&receipt_json)
+            .unwrap()
+            .digest()
+            .unwrap();
+        let mut prefixed_receipt_json = receipt_json.clone();
+        prefixed_receipt_json.act_digest = format!("0x{}", receipt_json.act_digest);
+        let result = parse_eos_action_receipt_json(&prefixed_receipt_json)
+            .unwrap()
+            .digest()
+            .unwrap();
+        assert_eq!(result, expected_result);
+        let mut short_receipt_json = receipt_json;
+        short_receipt_json.act_digest.truncate(62);
+        assert!(parse_eos_action_receipt_json(&short_receipt_json).is_err());
+    }
 }
@@ -1,19 +1,59 @@
 use crate::{
+    error::AppError,
+    hex_utils::{decode_checksum256, CHECKSUM256_LENGTH},
     state::State,
-    types::{EosBlock, EosBlockJson, Result},
+    types::{BlockTimestamp, Checksum256Bytes, EosBlock, EosBlockJson, Result},
 };
+use eos_chain::AccountName;
+use std::str::FromStr;
+
+pub fn get_block_num_from_block_id(block_id: &str) -> Result<u64> {
+    // NOTE: The first 4 bytes of an EOS block id are its big-endian block number.
+    parse_checksum256("block_id", block_id).map(|block_id_bytes| {
+        u32::from_be_bytes([
+            block_id_bytes[0],
+            block_id_bytes[1],
+            block_id_bytes[2],
+            block_id_bytes[3],
+        ]) as u64
+    })
+}
+
+fn parse_checksum256(field: &str, hex: &str) -> Result<Checksum256Bytes> {
+    decode_checksum256(hex).map_err(|_| {
+        AppError::Custom(format!(
+            "✘ Block `{}` must be {} bytes: {}",
+            field, CHECKSUM256_LENGTH, hex
+        ))
+    })
+}
+
+fn get_block_num_from_block_json(block_json: &EosBlockJson) -> Result<u64> {
+    let block_num = get_block_num_from_block_id(&block_json.block_id)?;
+    match block_json.block_num {
+        Some(supplied_block_num) if supplied_block_num != block_num => {
+            Err(AppError::Custom(format!(
+                "✘ Block number mismatch!\n✘ Supplied: {}\n✘ From block id: {}",
+                supplied_block_num, block_num
+            )))
+        }
+        _ => Ok(block_num),
+    }
+}
 
 pub fn parse_eos_block_json(block_json: &EosBlockJson) -> Result<EosBlock> {
     Ok(EosBlock {
+        block_num: get_block_num_from_block_json(block_json)?,
+        timestamp: BlockTimestamp::from_str(&block_json.timestamp)?,
         confirmed: block_json.confirmed,
-        previous: block_json.previous.clone(),
-        producer: block_json.producer.clone(),
+        previous: parse_checksum256("previous", &block_json.previous)?,
+        producer: AccountName::from_str(&block_json.producer)?,
         new_producers: serde_json::Value::Null,
-        block_id: hex::decode(&block_json.block_id)?,
-        action_mroot: block_json.action_mroot.clone(),
+        block_id: parse_checksum256("block_id", &block_json.block_id)?.to_vec(),
+        action_mroot: parse_checksum256("action_mroot", &block_json.action_mroot)?,
         schedule_version: block_json.schedule_version,
         header_extensions: block_json.header_extensions.clone(),
-        transaction_mroot: block_json.transaction_mroot.clone(),
+        transaction_mroot: parse_checksum256("transaction_mroot", &block_json.transaction_mroot)?,
     })
 }
 
@@ -37,4 +77,40 @@ mod tests {
             panic!("Error parsing EOS block: {}", e)
         }
     }
+
+    #[test]
+    fn should_parse_typed_fields_of_eos_block_json() {
+        let json = get_sample_submission_json_n(1).unwrap();
+        let result = parse_eos_block_json(&json.block).unwrap();
+        assert_eq!(result.block_num, 84756340);
+        assert_eq!(result.timestamp.to_string(), json.block.timestamp);
+        assert_eq!(result.producer.to_string(), json.block.producer);
+        assert_eq!(hex::encode(result.action_mroot), json.block.action_mroot);
+    }
+
+    #[test]
+    fn should_fail_to_parse_eos_block_json_with_mismatched_block_num() {
+        let mut json = get_sample_submission_json_n(1).unwrap();
+        json.block.block_num = Some(84756341);
+        assert!(parse_eos_block_json(&json.block).is_err());
+    }
+
+    #[test]
+    fn should_get_block_num_from_block_id() {
+        let json = get_sample_submission_json_n(1).unwrap();
+        let result = get_block_num_from_block_id(&json.block.block_id).unwrap();
+        assert_eq!(result, 84756340);
+    }
+
+    #[test]
+    fn should_parse_prefixed_block_id_but_reject_short_ones() {
+        let mut json = get_sample_submission_json_n(1).unwrap();
+        let block_id = json.block.block_id.clone();
+        json.block.block_id = format!("0x{}", block_id);
+        let result = parse_eos_block_json(&json.block).unwrap();
+        assert_eq!(hex::encode(result.block_id), block_id);
+        json.block.block_id = block_id[..62].to_string();
+        let err = parse_eos_block_json(&json.block).unwrap_err().to_string();
+        assert!(err.contains("block_id"));
+    }
 }
@@ -1,7 +1,10 @@
 use crate::{
     error::AppError,
-    state::State,
-    types::{EosInputJson, Result},
+    find_index_of_action::action_receipt_matches_selector,
+    types::{
+        EosActionJsons, EosActionReceiptJsons, EosInputJson, ReceiptSelectorJson,
+        ReceiptSelectorJsons, Result,
+    },
 };
 
 pub fn parse_eos_input_json_string(eos_input_json_string: &str) -> Result<EosInputJson> {
@@ -11,16 +14,112 @@ pub fn parse_eos_input_json_string(eos_input_json_string: &str) -> Result<EosInp
     }
 }
 
-pub fn parse_input_json_string_and_put_in_state(state: State) -> Result<State> {
-    trace!("✔ Parsing input json...");
-    parse_eos_input_json_string(&state.cli_args.arg_JSON)
-        .and_then(|eos_input_json| state.add_eos_input_json(eos_input_json))
+pub fn get_action_jsons_from_input_json(input_json: &EosInputJson) -> Result<EosActionJsons> {
+    match (&input_json.action, &input_json.actions) {
+        (Some(action_json), None) => Ok(vec![action_json.clone()]),
+        (None, Some(action_jsons)) if !action_jsons.is_empty() => Ok(action_jsons.clone()),
+        _ => Err(AppError::Custom(
+            "✘ Input JSON must contain either an `action` or a non-empty `actions` array!"
+                .to_string(),
+        )),
+    }
+}
+
+pub fn get_selector_jsons_from_input_json(
+    input_json: &EosInputJson,
+) -> Result<ReceiptSelectorJsons> {
+    let num_actions = get_action_jsons_from_input_json(input_json)?.len();
+    match (&input_json.selector, &input_json.selectors) {
+        (None, None) => Ok(vec![Default::default(); num_actions]),
+        (Some(selector_json), None) if input_json.action.is_some() => {
+            Ok(vec![selector_json.clone()])
+        }
+        (None, Some(selector_jsons))
+            if input_json.actions.is_some() && selector_jsons.len() == num_actions =>
+        {
+            Ok(selector_jsons.clone())
+        }
+        _ => Err(AppError::Custom(
+            "✘ A `selector` may only accompany an `action`, & `selectors` must be an array the same length as `actions`!"
+                .to_string(),
+        )),
+    }
+}
+
+/// Puts the receipts in the input, plus an action for each receipt matched by the `selector`,
+/// each pinned to its receipt. The receipts & their actions must be sorted by global sequence.
+pub fn put_actions_matching_selector_in_input_json(
+    mut input_json: EosInputJson,
+    action_receipts: EosActionReceiptJsons,
+    action_jsons: EosActionJsons,
+    selector: &ReceiptSelectorJson,
+) -> Result<EosInputJson> {
+    let (selected_action_jsons, selectors): (EosActionJsons, ReceiptSelectorJsons) = (0
+        ..action_receipts.len())
+        .filter(|index| action_receipt_matches_selector(*index, &action_receipts, selector))
+        .map(|index| {
+            (
+                action_jsons[index].clone(),
+                ReceiptSelectorJson {
+                    global_sequence: Some(action_receipts[index].global_sequence),
+                    ..Default::default()
+                },
+            )
+        })
+        .unzip();
+    info!("✔ {} action(s) in traces match selector", selectors.len());
+    input_json.action_receipts = action_receipts;
+    match selectors.len() {
+        0 => {
+            return Err(AppError::Custom(
+                "✘ No action in the block's traces matches the selector!".to_string(),
+            ))
+        }
+        1 => {
+            input_json.action = selected_action_jsons.into_iter().next();
+            input_json.selector = selectors.into_iter().next();
+        }
+        _ => {
+            input_json.actions = Some(selected_action_jsons);
+            input_json.selectors = Some(selectors);
+        }
+    };
+    Ok(input_json)
+}
+
+/// Puts the receipts in the input. If it has no `action` or `actions`, its `selector` picks them
+/// out of the supplied actions, as `put_actions_matching_selector_in_input_json` does.
+pub fn put_action_receipts_and_maybe_selected_actions_in_input_json(
+    mut input_json: EosInputJson,
+    action_receipts: EosActionReceiptJsons,
+    action_jsons: EosActionJsons,
+) -> Result<EosInputJson> {
+    let has_actions = input_json.action.is_some() || input_json.actions.is_some();
+    match (has_actions, input_json.selector.take()) {
+        (false, Some(selector)) => put_actions_matching_selector_in_input_json(
+            input_json,
+            action_receipts,
+            action_jsons,
+            &selector,
+        ),
+        (_, selector) => {
+            input_json.selector = selector;
+            input_json.action_receipts = action_receipts;
+            Ok(input_json)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::get_sample_submission_string_n;
+    use crate::{
+        test_utils::{
+            get_sample_batch_submission_string_n, get_sample_submission_json_n,
+            get_sample_submission_string_n,
+        },
+        types::ReceiptSelectorJson,
+    };
 
     #[test]
     fn should_parse_input_json_string() {
@@ -29,4 +128,44 @@ mod tests {
         let result = parse_eos_input_json_string(&string).unwrap();
         assert_eq!(result.action_receipts.len(), expected_num_action_receipts);
     }
+
+    #[test]
+    fn should_get_action_jsons_from_input_json_with_single_action() {
+        let json = get_sample_submission_json_n(1).unwrap();
+        let result = get_action_jsons_from_input_json(&json).unwrap();
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn should_get_action_jsons_from_input_json_with_batch_of_actions() {
+        let string = get_sample_batch_submission_string_n(1).unwrap();
+        let json = parse_eos_input_json_string(&string).unwrap();
+        let result = get_action_jsons_from_input_json(&json).unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn should_fail_to_get_action_jsons_from_input_json_with_no_actions() {
+        let mut json = get_sample_submission_json_n(1).unwrap();
+        json.action = None;
+        assert!(get_action_jsons_from_input_json(&json).is_err());
+    }
+
+    #[test]
+    fn should_get_default_selector_jsons_when_none_supplied() {
+        let json = get_sample_submission_json_n(1).unwrap();
+        let result = get_selector_jsons_from_input_json(&json).unwrap();
+        assert_eq!(result, vec![ReceiptSelectorJson::default()]);
+    }
+
+    #[test]
+    fn should_fail_to_get_selector_jsons_if_they_do_not_match_actions() {
+        let mut json = get_sample_submission_json_n(1).unwrap();
+        json.selectors = Some(vec![ReceiptSelectorJson::default()]);
+        assert!(get_selector_jsons_from_input_json(&json).is_err());
+        let string = get_sample_batch_submission_string_n(1).unwrap();
+        let mut batch_json = parse_eos_input_json_string(&string).unwrap();
+        batch_json.selectors = Some(vec![ReceiptSelectorJson::default()]);
+        assert!(get_selector_jsons_from_input_json(&batch_json).is_err());
+    }
 }
@@ -0,0 +1,280 @@
+use crate::{
+    build_merkle_tree::build_merkle_tree_and_put_in_state,
+    error::AppError,
+    find_index_of_action::{
+        find_indices_of_actions_and_put_in_state, put_all_action_receipt_indices_in_state,
+    },
+    generate_block_proof::maybe_generate_block_proof_and_put_in_state,
+    generate_multi_proof::generate_multi_proof_output,
+    generate_output::{generate_action_receipt_outputs, generate_outputs},
+    generate_proof::generate_proofs_and_add_to_state,
+    generate_transaction_proof::generate_transaction_proof_outputs,
+    history_api::maybe_get_action_receipts_from_get_actions_response,
+    parse_eos_action::parse_eos_action_jsons_and_put_in_state,
+    parse_eos_action_receipts::parse_eos_action_receipt_jsons_and_put_in_state,
+    parse_eos_block::parse_eos_block_json_and_put_in_state,
+    parse_input_json::parse_eos_input_json_string,
+    state::State,
+    state_history::maybe_get_action_receipts_from_state_history_result,
+    trace_api::maybe_get_action_receipts_from_trace_api_block,
+    types::{
+        ActionReceiptOutputs, Byte, Bytes, EosBlockJson, EosInputJson, MultiProofOutput, Output,
+        Outputs, Result, TransactionProofOutput, TransactionProofOutputs, VerificationOutput,
+    },
+    validate_action_mroot::validate_action_receipt_merkle_root,
+    validate_block_id::{parse_and_validate_eos_block_json, validate_block_id},
+    validate_producer_signature::maybe_validate_producer_signature,
+    verify_output::{parse_output_json_string, verify_block_proof, verify_output},
+    verify_proof::verify_proofs_in_state,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GenerateOptions {
+    pub all: bool,
+    pub multi_proof: bool,
+}
+
+pub struct ProofMaker;
+
+fn maybe_get_action_receipts_from_traces(input_json: EosInputJson) -> Result<EosInputJson> {
+    maybe_get_action_receipts_from_state_history_result(input_json)
+        .and_then(maybe_get_action_receipts_from_get_actions_response)
+        .and_then(maybe_get_action_receipts_from_trace_api_block)
+}
+
+fn put_eos_input_json_in_state(input_json: &EosInputJson) -> Result<State> {
+    let input_json = maybe_get_action_receipts_from_traces(input_json.clone())?;
+    State::init().and_then(|state| state.add_eos_input_json(input_json))
+}
+
+fn validate_block_and_put_in_state(state: State) -> Result<State> {
+    parse_eos_block_json_and_put_in_state(state)
+        .and_then(validate_block_id)
+        .and_then(maybe_validate_producer_signature)
+        .and_then(maybe_generate_block_proof_and_put_in_state)
+}
+
+fn validate_action_receipts_and_put_in_state(state: State) -> Result<State> {
+    validate_block_and_put_in_state(state)
+        .and_then(parse_eos_action_receipt_jsons_and_put_in_state)
+        .and_then(build_merkle_tree_and_put_in_state)
+        .and_then(validate_action_receipt_merkle_root)
+}
+
+fn find_actions_and_put_in_state(input_json: &EosInputJson) -> Result<State> {
+    put_eos_input_json_in_state(input_json)
+        .and_then(parse_eos_action_jsons_and_put_in_state)
+        .and_then(validate_action_receipts_and_put_in_state)
+        .and_then(find_indices_of_actions_and_put_in_state)
+}
+
+impl ProofMaker {
+    /// Generates & verifies a proof for the single `action` in the input.
+    pub fn generate(input_json: &EosInputJson) -> Result<Output> {
+        let mut outputs = Self::generate_batch(input_json)?;
+        match outputs.len() {
+            1 => Ok(outputs.remove(0)),
+            _ => Err(AppError::Custom(
+                "✘ Use `generate_batch` to generate proofs for many `actions`!".to_string(),
+            )),
+        }
+    }
+
+    /// Generates & verifies a proof for each of the `action` or `actions` in the input, in order.
+    pub fn generate_batch(input_json: &EosInputJson) -> Result<Outputs> {
+        find_actions_and_put_in_state(input_json)
+            .and_then(generate_proofs_and_add_to_state)
+            .and_then(verify_proofs_in_state)
+            .and_then(|state| generate_outputs(&state))
+    }
+
+    /// Generates & verifies a proof for every action receipt in the input.
+    pub fn generate_all(input_json: &EosInputJson) -> Result<ActionReceiptOutputs> {
+        put_eos_input_json_in_state(input_json)
+            .and_then(validate_action_receipts_and_put_in_state)
+            .and_then(put_all_action_receipt_indices_in_state)
+            .and_then(generate_proofs_and_add_to_state)
+            .and_then(verify_proofs_in_state)
+            .and_then(|state| generate_action_receipt_outputs(&state))
+    }
+
+    /// Generates & verifies one multi-proof covering all the `action` or `actions` in the input.
+    pub fn generate_multi_proof(input_json: &EosInputJson) -> Result<MultiProofOutput> {
+        find_actions_and_put_in_state(input_json)
+            .and_then(|state| generate_multi_proof_output(&state))
+    }
+
+    /// Generates & verifies a proof for every transaction receipt in the input's `block`, tying
+    /// each to the block's `transaction_mroot`.
+    pub fn generate_transaction_proofs(
+        input_json: &EosInputJson,
+    ) -> Result<TransactionProofOutputs> {
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json.clone()))
+            .and_then(validate_block_and_put_in_state)
+            .and_then(|state| generate_transaction_proof_outputs(&state, None))
+    }
+
+    /// Generates & verifies a proof for the receipt of the transaction with the given id.
+    pub fn generate_transaction_proof(
+        input_json: &EosInputJson,
+        tx_id: &str,
+    ) -> Result<TransactionProofOutput> {
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json.clone()))
+            .and_then(validate_block_and_put_in_state)
+            .and_then(|state| generate_transaction_proof_outputs(&state, Some(tx_id)))
+            .map(|mut outputs| outputs.remove(0))
+    }
+
+    /// Validates a trusted block header against its `block_id`, returning its `action_mroot`.
+    pub fn get_action_mroot_from_trusted_block(block_json: &EosBlockJson) -> Result<Bytes> {
+        parse_and_validate_eos_block_json(block_json)
+            .map(|eos_block| eos_block.action_mroot.to_vec())
+    }
+
+    /// Verifies a previously generated `Output` against a trusted `action_mroot`.
+    pub fn verify(output: &Output, trusted_action_mroot: &[Byte]) -> Result<VerificationOutput> {
+        info!("✔ Verifying proof...");
+        verify_output(output, trusted_action_mroot).map(|_| VerificationOutput {
+            tx_id: output.tx_id.clone(),
+            block_id: output.block_id.clone(),
+            action_index: output.action_index,
+            action_mroot: hex::encode(trusted_action_mroot),
+            blockroot: None,
+        })
+    }
+
+    /// Verifies a previously generated `Output` against the trusted `blockroot` of a later block,
+    /// via its `block_proof` & the `action_mroot` in its serialized block header.
+    pub fn verify_against_blockroot(
+        output: &Output,
+        trusted_blockroot: &[Byte],
+    ) -> Result<VerificationOutput> {
+        info!("✔ Verifying block proof...");
+        verify_block_proof(output, trusted_blockroot)
+            .and_then(|action_mroot| Self::verify(output, &action_mroot))
+            .map(|verification_output| VerificationOutput {
+                blockroot: Some(hex::encode(trusted_blockroot)),
+                ..verification_output
+            })
+    }
+
+    /// Takes & returns JSON strings exactly as the `generate` command of the CLI does.
+    pub fn generate_json_string(
+        input_json_string: &str,
+        options: GenerateOptions,
+    ) -> Result<String> {
+        // NOTE: A selector over traces may pick many actions, making this a batch.
+        let input_json = parse_eos_input_json_string(input_json_string)
+            .and_then(maybe_get_action_receipts_from_traces)?;
+        if options.multi_proof {
+            Ok(serde_json::to_string(&Self::generate_multi_proof(
+                &input_json,
+            )?)?)
+        } else if options.all {
+            Ok(serde_json::to_string(&Self::generate_all(&input_json)?)?)
+        } else if input_json.actions.is_some() {
+            Ok(serde_json::to_string(&Self::generate_batch(&input_json)?)?)
+        } else {
+            Ok(serde_json::to_string(&Self::generate(&input_json)?)?)
+        }
+    }
+
+    /// Takes & returns JSON strings exactly as the `generate-tx-proof` command of the CLI does.
+    pub fn generate_transaction_proof_json_string(
+        input_json_string: &str,
+        tx_id: Option<&str>,
+    ) -> Result<String> {
+        let input_json = parse_eos_input_json_string(input_json_string)?;
+        match tx_id {
+            Some(tx_id) => Ok(serde_json::to_string(&Self::generate_transaction_proof(
+                &input_json,
+                tx_id,
+            )?)?),
+            None => Ok(serde_json::to_string(&Self::generate_transaction_proofs(
+                &input_json,
+            )?)?),
+        }
+    }
+
+    /// Takes & returns JSON strings exactly as the `verify` command of the CLI does.
+    pub fn verify_json_string(
+        output_json_string: &str,
+        trusted_action_mroot: &[Byte],
+    ) -> Result<String> {
+        let output = parse_output_json_string(output_json_string)?;
+        Ok(serde_json::to_string(&Self::verify(
+            &output,
+            trusted_action_mroot,
+        )?)?)
+    }
+
+    /// Takes & returns JSON strings as the `verify` command of the CLI does with `--blockroot`.
+    pub fn verify_json_string_against_blockroot(
+        output_json_string: &str,
+        trusted_blockroot: &[Byte],
+    ) -> Result<String> {
+        let output = parse_output_json_string(output_json_string)?;
+        Ok(serde_json::to_string(&Self::verify_against_blockroot(
+            &output,
+            trusted_blockroot,
+        )?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eos_merkle_utils::verify_merkle_proof,
+        test_utils::{
+            get_sample_batch_submission_string_n, get_sample_eos_block_n,
+            get_sample_submission_json_n, get_sample_submission_string_n,
+        },
+    };
+
+    #[test]
+    fn should_generate_and_verify_output_via_typed_api() {
+        let input_json = get_sample_submission_json_n(1).unwrap();
+        let output = ProofMaker::generate(&input_json).unwrap();
+        assert_eq!(output.action_index, 5);
+        let action_mroot = get_sample_eos_block_n(1).unwrap().action_mroot.to_vec();
+        let result = ProofMaker::verify(&output, &action_mroot).unwrap();
+        assert_eq!(result.tx_id, output.tx_id);
+        assert_eq!(result.action_mroot, hex::encode(action_mroot));
+    }
+
+    #[test]
+    fn should_not_generate_single_output_for_batch_input() {
+        let input_json =
+            parse_eos_input_json_string(&get_sample_batch_submission_string_n(1).unwrap()).unwrap();
+        assert!(ProofMaker::generate(&input_json).is_err());
+        assert_eq!(ProofMaker::generate_batch(&input_json).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn should_generate_transaction_proof_via_typed_api() {
+        let input_json = get_sample_submission_json_n(1).unwrap();
+        let tx_id = "9051ddcb328050217c59b61a6cd1ec8435146bf3da5d74de1c27a5ab6e771539";
+        let output = ProofMaker::generate_transaction_proof(&input_json, tx_id).unwrap();
+        let transaction_mroot = get_sample_eos_block_n(1)
+            .unwrap()
+            .transaction_mroot
+            .to_vec();
+        assert_eq!(output.transaction_mroot, hex::encode(&transaction_mroot));
+        assert!(verify_merkle_proof(&output.transaction_proof, &transaction_mroot).unwrap());
+        let result = ProofMaker::generate_transaction_proof_json_string(
+            &get_sample_submission_string_n(1).unwrap(),
+            None,
+        )
+        .unwrap();
+        assert!(result.starts_with('['));
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_against_wrong_action_mroot() {
+        let output = ProofMaker::generate(&get_sample_submission_json_n(1).unwrap()).unwrap();
+        assert!(ProofMaker::verify(&output, &[0u8; 32]).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    error::AppError,
+    types::{Byte, Bytes, Result},
+};
+use flate2::read::GzDecoder;
+use std::{fs::read, io::Read};
+
+const GZIP_MAGIC_BYTES: [Byte; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC_BYTES: [Byte; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn decompress_gzip_bytes(bytes: &[Byte]) -> Result<Bytes> {
+    let mut decompressed_bytes = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decompressed_bytes)?;
+    Ok(decompressed_bytes)
+}
+
+fn decompress_zstd_bytes(bytes: &[Byte]) -> Result<Bytes> {
+    Ok(zstd::stream::decode_all(bytes)?)
+}
+
+pub fn maybe_decompress_bytes(bytes: Bytes) -> Result<Bytes> {
+    if bytes.starts_with(&GZIP_MAGIC_BYTES) {
+        info!("✔ Decompressing gzip input...");
+        decompress_gzip_bytes(&bytes)
+    } else if bytes.starts_with(&ZSTD_MAGIC_BYTES) {
+        info!("✔ Decompressing zstd input...");
+        decompress_zstd_bytes(&bytes)
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn convert_bytes_to_string(bytes: Bytes) -> Result<String> {
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::Custom(format!("✘ Input is not valid UTF-8: {}", e)))
+}
+
+pub fn read_reader_to_string<R: Read>(mut reader: R) -> Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    maybe_decompress_bytes(bytes).and_then(convert_bytes_to_string)
+}
+
+pub fn read_file_to_bytes(path: &str) -> Result<Bytes> {
+    info!("✔ Reading file @ path: {}...", path);
+    read(path)
+        .map_err(|e| {
+            AppError::Custom(format!(
+                "✘ I/O error reading file @ path: {}\n✘ {}",
+                path, e
+            ))
+        })
+        .and_then(maybe_decompress_bytes)
+}
+
+pub fn read_file_to_string(path: &str) -> Result<String> {
+    read_file_to_bytes(path).and_then(convert_bytes_to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_sample_submission_string_n;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const SAMPLE_PATH: &str = "src/test_utils/sample-material-1.json";
+
+    fn get_gzipped_bytes(bytes: &[Byte]) -> Bytes {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn should_read_json_from_reader() {
+        let json_string = get_sample_submission_string_n(1).unwrap();
+        let result = read_reader_to_string(json_string.as_bytes()).unwrap();
+        assert_eq!(result, json_string);
+    }
+
+    #[test]
+    fn should_read_uncompressed_file_to_string() {
+        let result = read_file_to_string(SAMPLE_PATH).unwrap();
+        assert_eq!(result, get_sample_submission_string_n(1).unwrap());
+    }
+
+    #[test]
+    fn should_fail_to_read_missing_file_naming_its_path() {
+        let path = "src/test_utils/no-such-file.json";
+        let err = read_file_to_string(path).unwrap_err().to_string();
+        assert!(err.contains("I/O error"));
+        assert!(err.contains(path));
+    }
+
+    #[test]
+    fn should_read_gzipped_bytes_to_string() {
+        let json_string = get_sample_submission_string_n(1).unwrap();
+        let compressed_bytes = get_gzipped_bytes(json_string.as_bytes());
+        assert!(compressed_bytes.starts_with(&GZIP_MAGIC_BYTES));
+        let result = read_reader_to_string(&compressed_bytes[..]).unwrap();
+        assert_eq!(result, json_string);
+    }
+
+    #[test]
+    fn should_read_zstd_compressed_bytes_to_string() {
+        let json_string = get_sample_submission_string_n(1).unwrap();
+        let compressed_bytes = zstd::stream::encode_all(json_string.as_bytes(), 0).unwrap();
+        assert!(compressed_bytes.starts_with(&ZSTD_MAGIC_BYTES));
+        let result = read_reader_to_string(&compressed_bytes[..]).unwrap();
+        assert_eq!(result, json_string);
+    }
+
+    #[test]
+    fn should_fail_to_read_corrupt_gzipped_bytes() {
+        let json_string = get_sample_submission_string_n(1).unwrap();
+        let mut compressed_bytes = get_gzipped_bytes(json_string.as_bytes());
+        compressed_bytes.truncate(compressed_bytes.len() / 2);
+        assert!(read_reader_to_string(&compressed_bytes[..]).is_err());
+    }
+}
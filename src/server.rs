@@ -0,0 +1,393 @@
+use eos_action_proof_maker::{
+    error::AppError,
+    types::{Bytes, EosInputJson, Result},
+    ProofMaker,
+};
+use std::{
+    io::Read,
+    panic::{catch_unwind, AssertUnwindSafe},
+    time::Instant,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const MAX_BODY_NUM_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct EndpointMetrics {
+    pub requests: u64,
+    pub errors: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ServerMetrics {
+    pub uptime_seconds: u64,
+    pub requests: u64,
+    pub generate: EndpointMetrics,
+    pub generate_batch: EndpointMetrics,
+    pub verify: EndpointMetrics,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthOutput {
+    pub status: String,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrorOutput {
+    pub error: String,
+}
+
+struct ServerState {
+    start_time: Instant,
+    metrics: ServerMetrics,
+}
+
+fn get_query_param<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    url.split_once('?').and_then(|(_, query)| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+    })
+}
+
+fn generate(body: &str) -> Result<String> {
+    let input_json: EosInputJson = serde_json::from_str(body)?;
+    Ok(serde_json::to_string(&ProofMaker::generate(&input_json)?)?)
+}
+
+fn generate_batch(body: &str) -> Result<String> {
+    let input_json: EosInputJson = serde_json::from_str(body)?;
+    Ok(serde_json::to_string(&ProofMaker::generate_batch(
+        &input_json,
+    )?)?)
+}
+
+fn verify(url: &str, body: &str) -> Result<String> {
+    match get_query_param(url, "action_mroot") {
+        Some(action_mroot) => ProofMaker::verify_json_string(
+            body,
+            &hex::decode(action_mroot.trim_start_matches("0x"))?,
+        ),
+        None => Err(AppError::Custom(
+            "✘ Pass a trusted `action_mroot` query parameter to verify against!".to_string(),
+        )),
+    }
+}
+
+fn get_health_output() -> Result<String> {
+    Ok(serde_json::to_string(&HealthOutput {
+        status: "ok".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })?)
+}
+
+fn get_metrics_output(state: &ServerState) -> Result<String> {
+    let mut metrics = state.metrics.clone();
+    metrics.uptime_seconds = state.start_time.elapsed().as_secs();
+    Ok(serde_json::to_string(&metrics)?)
+}
+
+fn handle_endpoint_result(metrics: &mut EndpointMetrics, result: Result<String>) -> (u16, String) {
+    metrics.requests += 1;
+    match result {
+        Ok(output) => (200, output),
+        Err(e) => {
+            metrics.errors += 1;
+            error!("{}", e);
+            (400, get_error_output(&e.to_string()))
+        }
+    }
+}
+
+fn get_error_output(msg: &str) -> String {
+    serde_json::to_string(&ErrorOutput {
+        error: msg.to_string(),
+    })
+    .unwrap_or_default()
+}
+
+fn route_request(state: &mut ServerState, method: &Method, url: &str, body: &str) -> (u16, String) {
+    let path = url.split('?').next().unwrap_or_default();
+    state.metrics.requests += 1;
+    info!("✔ Handling request: {} {}", method, path);
+    match (method, path) {
+        (Method::Get, "/health") => (200, get_health_output().unwrap_or_default()),
+        (Method::Get, "/metrics") => (200, get_metrics_output(state).unwrap_or_default()),
+        (Method::Post, "/generate") => {
+            handle_endpoint_result(&mut state.metrics.generate, generate(body))
+        }
+        (Method::Post, "/generate_batch") => {
+            handle_endpoint_result(&mut state.metrics.generate_batch, generate_batch(body))
+        }
+        (Method::Post, "/verify") => {
+            handle_endpoint_result(&mut state.metrics.verify, verify(url, body))
+        }
+        (_, "/health")
+        | (_, "/metrics")
+        | (_, "/generate")
+        | (_, "/generate_batch")
+        | (_, "/verify") => (405, get_error_output("✘ Method not allowed!")),
+        _ => (404, get_error_output("✘ Endpoint not found!")),
+    }
+}
+
+fn catch_handler_panic<F: FnOnce() -> (u16, String)>(handler: F) -> (u16, String) {
+    // NOTE: Every request is handled on the one thread, so a panicking handler mustn't kill it.
+    catch_unwind(AssertUnwindSafe(handler)).unwrap_or_else(|_| {
+        error!("✘ Panicked handling request!");
+        (500, get_error_output("✘ Internal server error!"))
+    })
+}
+
+fn get_body_too_large_output() -> String {
+    get_error_output(&format!(
+        "✘ Request body exceeds the maximum of {} bytes!",
+        MAX_BODY_NUM_BYTES
+    ))
+}
+
+fn read_body<R: Read>(reader: R) -> Result<Bytes> {
+    // NOTE: Reads one byte past the limit so an oversized body can be detected.
+    let mut bytes = Vec::new();
+    reader
+        .take(MAX_BODY_NUM_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn convert_body_to_string(bytes: Bytes) -> Result<String> {
+    // NOTE: Unlike input files, request bodies are never decompressed, lest they expand unbounded.
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::Custom(format!("✘ Request body is not valid UTF-8: {}", e)))
+}
+
+fn handle_request(state: &mut ServerState, mut request: Request) -> Result<()> {
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let (status, output) = match request.body_length() {
+        Some(num_bytes) if num_bytes > MAX_BODY_NUM_BYTES => (413, get_body_too_large_output()),
+        _ => match read_body(request.as_reader()) {
+            Ok(bytes) if bytes.len() > MAX_BODY_NUM_BYTES => (413, get_body_too_large_output()),
+            Ok(bytes) => match convert_body_to_string(bytes) {
+                Ok(body) => catch_handler_panic(|| route_request(state, &method, &url, &body)),
+                Err(e) => (400, get_error_output(&e.to_string())),
+            },
+            Err(e) => (400, get_error_output(&e.to_string())),
+        },
+    };
+    info!(
+        "✔ Responding to {} {} with status {} & a {} byte body",
+        method,
+        url,
+        status,
+        output.len()
+    );
+    let content_type = Header::from_bytes("Content-Type", JSON_CONTENT_TYPE)
+        .map_err(|_| AppError::Custom("✘ Error creating content type header!".to_string()))?;
+    Ok(request.respond(
+        Response::from_string(output)
+            .with_status_code(status)
+            .with_header(content_type),
+    )?)
+}
+
+pub fn serve(server: Server) -> Result<String> {
+    let mut state = ServerState {
+        start_time: Instant::now(),
+        metrics: ServerMetrics::default(),
+    };
+    for request in server.incoming_requests() {
+        if let Err(e) = handle_request(&mut state, request) {
+            error!("{}", e);
+        }
+    }
+    Ok("✔ Server stopped!".to_string())
+}
+
+pub fn bind_and_serve(address: &str) -> Result<String> {
+    let server = Server::http(address).map_err(|e| {
+        AppError::Custom(format!(
+            "✘ Error binding server to address: {}\n✘ {}",
+            address, e
+        ))
+    })?;
+    info!("✔ Serving on: {}", address);
+    serve(server)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eos_action_proof_maker::read_input_file::read_file_to_string;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{
+        io::{repeat, Write},
+        net::{SocketAddr, TcpStream},
+        thread,
+    };
+
+    const SAMPLE_PATH: &str = "src/test_utils/sample-material-1.json";
+
+    fn start_test_server() -> SocketAddr {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap();
+        thread::spawn(move || serve(server));
+        address
+    }
+
+    fn send_request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        send_request_with_bytes(address, method, path, body.as_bytes())
+    }
+
+    fn send_request_with_bytes(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n",
+            method,
+            path,
+            body.len(),
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    fn get_sample_action_mroot() -> String {
+        let input_json: EosInputJson =
+            serde_json::from_str(&read_file_to_string(SAMPLE_PATH).unwrap()).unwrap();
+        input_json.block.action_mroot
+    }
+
+    #[test]
+    fn should_get_query_param_from_url() {
+        let url = "/verify?foo=bar&action_mroot=0xabcd";
+        assert_eq!(get_query_param(url, "action_mroot"), Some("0xabcd"));
+        assert_eq!(get_query_param(url, "baz"), None);
+        assert_eq!(get_query_param("/verify", "action_mroot"), None);
+    }
+
+    #[test]
+    fn should_respond_to_health_check() {
+        let address = start_test_server();
+        let (status, body) = send_request(address, "GET", "/health", "");
+        assert_eq!(status, 200);
+        assert!(body.contains("\"status\":\"ok\""));
+    }
+
+    #[test]
+    fn should_generate_and_verify_proof_and_count_them_in_metrics() {
+        let address = start_test_server();
+        let input_json_string = read_file_to_string(SAMPLE_PATH).unwrap();
+        let (status, output) = send_request(address, "POST", "/generate", &input_json_string);
+        assert_eq!(status, 200);
+        let expected_output = ProofMaker::generate_json_string(
+            &input_json_string,
+            eos_action_proof_maker::GenerateOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(output, expected_output);
+        let verify_path = format!("/verify?action_mroot={}", get_sample_action_mroot());
+        let (status, verification) = send_request(address, "POST", &verify_path, &output);
+        assert_eq!(status, 200);
+        assert!(verification.contains(&format!(
+            "\"action_mroot\":\"{}\"",
+            get_sample_action_mroot()
+        )));
+        let (_, metrics) = send_request(address, "GET", "/metrics", "");
+        assert!(metrics.contains("\"generate\":{\"requests\":1,\"errors\":0}"));
+        assert!(metrics.contains("\"verify\":{\"requests\":1,\"errors\":0}"));
+    }
+
+    #[test]
+    fn should_generate_batch_of_proofs() {
+        let address = start_test_server();
+        let mut json: serde_json::Value =
+            serde_json::from_str(&read_file_to_string(SAMPLE_PATH).unwrap()).unwrap();
+        json["actions"] = json["block"]["transactions"][0]["trx"]["transaction"]["actions"].clone();
+        json["action"] = serde_json::Value::Null;
+        let input_json_string = json.to_string();
+        let (status, output) = send_request(address, "POST", "/generate_batch", &input_json_string);
+        assert_eq!(status, 200);
+        let outputs: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        assert_eq!(outputs.len(), 2);
+    }
+
+    #[test]
+    fn should_respond_with_error_json_and_count_errors() {
+        let address = start_test_server();
+        let (status, body) = send_request(address, "POST", "/generate", "{}");
+        assert_eq!(status, 400);
+        assert!(body.starts_with("{\"error\":"));
+        let (status, _) = send_request(address, "POST", "/verify", "{}");
+        assert_eq!(status, 400);
+        let (_, metrics) = send_request(address, "GET", "/metrics", "");
+        assert!(metrics.contains("\"generate\":{\"requests\":1,\"errors\":1}"));
+        assert!(metrics.contains("\"verify\":{\"requests\":1,\"errors\":1}"));
+    }
+
+    #[test]
+    fn should_respond_with_not_found_and_method_not_allowed() {
+        let address = start_test_server();
+        assert_eq!(send_request(address, "GET", "/nope", "").0, 404);
+        assert_eq!(send_request(address, "GET", "/generate", "").0, 405);
+    }
+
+    #[test]
+    fn should_reject_body_exceeding_max_size() {
+        let address = start_test_server();
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /generate HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_NUM_BYTES + 1
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413"));
+        assert!(response.contains("exceeds the maximum"));
+        let oversized_body = read_body(repeat(b' ').take(MAX_BODY_NUM_BYTES as u64 + 10)).unwrap();
+        assert_eq!(oversized_body.len(), MAX_BODY_NUM_BYTES + 1);
+    }
+
+    #[test]
+    fn should_not_decompress_request_body() {
+        let address = start_test_server();
+        let input_json_string = read_file_to_string(SAMPLE_PATH).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(input_json_string.as_bytes()).unwrap();
+        let compressed_body = encoder.finish().unwrap();
+        let (status, body) =
+            send_request_with_bytes(address, "POST", "/generate", &compressed_body);
+        assert_eq!(status, 400);
+        assert!(body.contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn should_respond_with_internal_server_error_if_handler_panics() {
+        let (status, body) = catch_handler_panic(|| panic!("boom"));
+        assert_eq!(status, 500);
+        assert!(body.contains("Internal server error"));
+        let mut state = ServerState {
+            start_time: Instant::now(),
+            metrics: ServerMetrics::default(),
+        };
+        let (status, _) =
+            catch_handler_panic(|| route_request(&mut state, &Method::Get, "/health", ""));
+        assert_eq!(status, 200);
+    }
+}
@@ -1,19 +1,24 @@
 use crate::{
+    eos_merkle_utils::MerkleTree,
     error::AppError,
-    parse_cli_args::CliArgs,
-    types::{EosActionReceipts, EosBlock, EosInputJson, MerkleProof, Result},
+    types::{
+        Bytes, DigestScheme, EosActionReceipts, EosActions, EosBlock, EosInputJson, MerkleProof,
+        MerkleProofs, Result,
+    },
 };
-use eos_chain::Action as EosAction;
 
 #[derive(Debug)]
 pub struct State {
-    pub cli_args: CliArgs,
-    pub proof_index: Option<u32>,
     pub eos_block: Option<EosBlock>,
-    pub eos_action: Option<EosAction>,
-    pub merkle_proof: Option<MerkleProof>,
+    pub eos_actions: Option<EosActions>,
+    pub action_return_values: Option<Vec<Bytes>>,
+    pub proof_indices: Option<Vec<u32>>,
+    pub digest_schemes: Option<Vec<DigestScheme>>,
+    pub merkle_tree: Option<MerkleTree>,
+    pub merkle_proofs: Option<MerkleProofs>,
     pub eos_input_json: Option<EosInputJson>,
     pub eos_action_receipts: Option<EosActionReceipts>,
+    pub block_proof: Option<MerkleProof>,
 }
 
 fn get_not_in_state_err(substring: &str) -> String {
@@ -25,15 +30,18 @@ fn get_no_overwrite_state_err(substring: &str) -> String {
 }
 
 impl State {
-    pub fn init(cli_args: CliArgs) -> Result<State> {
+    pub fn init() -> Result<State> {
         Ok(State {
-            cli_args,
             eos_block: None,
-            eos_action: None,
-            proof_index: None,
-            merkle_proof: None,
+            eos_actions: None,
+            action_return_values: None,
+            merkle_tree: None,
+            proof_indices: None,
+            digest_schemes: None,
+            merkle_proofs: None,
             eos_input_json: None,
             eos_action_receipts: None,
+            block_proof: None,
         })
     }
 
@@ -57,21 +65,63 @@ impl State {
         }
     }
 
-    pub fn add_eos_action(mut self, eos_action: EosAction) -> Result<Self> {
+    pub fn add_eos_actions(mut self, eos_actions: EosActions) -> Result<Self> {
         trace!("✔ Adding EOS actions to state!");
-        match self.eos_action {
-            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("eos_action"))),
+        match self.eos_actions {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("eos_actions"))),
             None => {
-                self.eos_action = Some(eos_action);
+                self.eos_actions = Some(eos_actions);
                 Ok(self)
             }
         }
     }
 
-    pub fn get_eos_action(&self) -> Result<&EosAction> {
-        match &self.eos_action {
+    pub fn get_eos_actions(&self) -> Result<&EosActions> {
+        match &self.eos_actions {
             Some(actions) => Ok(actions),
-            None => Err(AppError::Custom(get_not_in_state_err("eos_action"))),
+            None => Err(AppError::Custom(get_not_in_state_err("eos_actions"))),
+        }
+    }
+
+    pub fn add_action_return_values(mut self, action_return_values: Vec<Bytes>) -> Result<Self> {
+        trace!("✔ Adding action return values to state!");
+        match self.action_return_values {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err(
+                "action_return_values",
+            ))),
+            None => {
+                self.action_return_values = Some(action_return_values);
+                Ok(self)
+            }
+        }
+    }
+
+    pub fn get_action_return_values(&self) -> Result<&Vec<Bytes>> {
+        match &self.action_return_values {
+            Some(action_return_values) => Ok(action_return_values),
+            None => Err(AppError::Custom(get_not_in_state_err(
+                "action_return_values",
+            ))),
+        }
+    }
+
+    pub fn add_digest_schemes(mut self, digest_schemes: Vec<DigestScheme>) -> Result<Self> {
+        trace!("✔ Adding digest schemes to state!");
+        match self.digest_schemes {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err(
+                "digest_schemes",
+            ))),
+            None => {
+                self.digest_schemes = Some(digest_schemes);
+                Ok(self)
+            }
+        }
+    }
+
+    pub fn get_digest_schemes(&self) -> Result<&Vec<DigestScheme>> {
+        match &self.digest_schemes {
+            Some(digest_schemes) => Ok(digest_schemes),
+            None => Err(AppError::Custom(get_not_in_state_err("digest_schemes"))),
         }
     }
 
@@ -93,39 +143,61 @@ impl State {
         }
     }
 
-    pub fn add_merkle_proof(mut self, merkle_proof: MerkleProof) -> Result<Self> {
-        trace!("✔ Adding mekle proof to state!");
-        match self.merkle_proof {
-            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("merkle_proof"))),
+    pub fn add_merkle_tree(mut self, merkle_tree: MerkleTree) -> Result<Self> {
+        trace!("✔ Adding merkle tree to state!");
+        match self.merkle_tree {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("merkle_tree"))),
             None => {
-                self.merkle_proof = Some(merkle_proof);
+                self.merkle_tree = Some(merkle_tree);
                 Ok(self)
             }
         }
     }
 
-    pub fn get_merkle_proof(&self) -> Result<&MerkleProof> {
-        match &self.merkle_proof {
-            Some(proof) => Ok(proof),
-            None => Err(AppError::Custom(get_not_in_state_err("merkle_proof"))),
+    pub fn get_merkle_tree(&self) -> Result<&MerkleTree> {
+        match &self.merkle_tree {
+            Some(tree) => Ok(tree),
+            None => Err(AppError::Custom(get_not_in_state_err("merkle_tree"))),
         }
     }
 
-    pub fn add_proof_index(mut self, proof_index: u32) -> Result<Self> {
-        trace!("✔ Adding mekle proof to state!");
-        match self.proof_index {
-            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("proof_index"))),
+    pub fn add_merkle_proofs(mut self, merkle_proofs: MerkleProofs) -> Result<Self> {
+        trace!("✔ Adding merkle proofs to state!");
+        match self.merkle_proofs {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err(
+                "merkle_proofs",
+            ))),
             None => {
-                self.proof_index = Some(proof_index);
+                self.merkle_proofs = Some(merkle_proofs);
                 Ok(self)
             }
         }
     }
 
-    pub fn get_proof_index(&self) -> Result<u32> {
-        match &self.proof_index {
-            Some(index) => Ok(*index),
-            None => Err(AppError::Custom(get_not_in_state_err("proof_index"))),
+    pub fn get_merkle_proofs(&self) -> Result<&MerkleProofs> {
+        match &self.merkle_proofs {
+            Some(proofs) => Ok(proofs),
+            None => Err(AppError::Custom(get_not_in_state_err("merkle_proofs"))),
+        }
+    }
+
+    pub fn add_proof_indices(mut self, proof_indices: Vec<u32>) -> Result<Self> {
+        trace!("✔ Adding proof indices to state!");
+        match self.proof_indices {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err(
+                "proof_indices",
+            ))),
+            None => {
+                self.proof_indices = Some(proof_indices);
+                Ok(self)
+            }
+        }
+    }
+
+    pub fn get_proof_indices(&self) -> Result<&Vec<u32>> {
+        match &self.proof_indices {
+            Some(indices) => Ok(indices),
+            None => Err(AppError::Custom(get_not_in_state_err("proof_indices"))),
         }
     }
 
@@ -153,4 +225,15 @@ impl State {
             ))),
         }
     }
+
+    pub fn add_block_proof(mut self, block_proof: MerkleProof) -> Result<Self> {
+        trace!("✔ Adding block proof to state!");
+        match self.block_proof {
+            Some(_) => Err(AppError::Custom(get_no_overwrite_state_err("block_proof"))),
+            None => {
+                self.block_proof = Some(block_proof);
+                Ok(self)
+            }
+        }
+    }
 }
@@ -0,0 +1,462 @@
+use crate::{
+    error::AppError,
+    hex_utils::{strip_hex_prefix, CHECKSUM256_LENGTH},
+    parse_eos_action::parse_eos_action_json,
+    parse_eos_action_receipts::parse_action_receipt_jsons,
+    parse_input_json::put_action_receipts_and_maybe_selected_actions_in_input_json,
+    types::{
+        AuthSequenceJson, AuthorizationJson, Byte, Bytes, EosActionJson, EosActionReceiptJson,
+        EosActionReceipts, EosActions, EosInputJson, Result, StateHistoryBlockTraces,
+    },
+    varuint_utils::deserialize_varuint32,
+};
+use eos_chain::AccountName;
+
+// NOTE: Indices into the variants of the state-history plugin's ABI which we know how to decode.
+const GET_BLOCKS_RESULT_V0_INDEX: u32 = 1;
+const TRANSACTION_TRACE_V0_INDEX: u32 = 0;
+const ACTION_TRACE_V0_INDEX: u32 = 0;
+const ACTION_TRACE_V1_INDEX: u32 = 1;
+const ACTION_RECEIPT_V0_INDEX: u32 = 0;
+const PARTIAL_TRANSACTION_V0_INDEX: u32 = 0;
+const WEBAUTHN_SIGNATURE_INDEX: u32 = 2;
+const SIGNATURE_LENGTH: usize = 65;
+
+/// Reads the little-endian, `fc::raw` packed binary format the state-history plugin speaks.
+struct StateHistoryReader<'a> {
+    bytes: &'a [Byte],
+    position: usize,
+}
+
+impl<'a> StateHistoryReader<'a> {
+    fn new(bytes: &'a [Byte]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [Byte]> {
+        match self.bytes.len().checked_sub(self.position) {
+            Some(remaining) if remaining >= length => {
+                self.position += length;
+                Ok(&self.bytes[self.position - length..self.position])
+            }
+            _ => Err(AppError::Custom(format!(
+                "✘ Unexpected end of state-history data reading {} bytes @ position {}!",
+                length, self.position
+            ))),
+        }
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[Byte; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_bool(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(AppError::Custom(format!(
+                "✘ Invalid state-history bool: {}",
+                byte
+            ))),
+        }
+    }
+
+    fn read_varuint32(&mut self) -> Result<u32> {
+        let (value, num_bytes) = deserialize_varuint32(&self.bytes[self.position..])?;
+        self.position += num_bytes;
+        Ok(value)
+    }
+
+    fn read_length(&mut self) -> Result<usize> {
+        Ok(self.read_varuint32()? as usize)
+    }
+
+    fn read_byte_vec(&mut self) -> Result<&'a [Byte]> {
+        let length = self.read_length()?;
+        self.read_bytes(length)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        String::from_utf8(self.read_byte_vec()?.to_vec())
+            .map_err(|e| AppError::Custom(format!("✘ Invalid state-history string: {}", e)))
+    }
+
+    fn read_name(&mut self) -> Result<String> {
+        Ok(AccountName::from(self.read_u64()?).to_string())
+    }
+
+    fn read_checksum256(&mut self) -> Result<String> {
+        Ok(hex::encode(self.read_bytes(CHECKSUM256_LENGTH)?))
+    }
+
+    fn read_variant_index(&mut self, type_name: &str, known_indices: &[u32]) -> Result<u32> {
+        let index = self.read_varuint32()?;
+        match known_indices.contains(&index) {
+            true => Ok(index),
+            false => Err(AppError::Custom(format!(
+                "✘ Unsupported state-history `{}` variant: {}",
+                type_name, index
+            ))),
+        }
+    }
+
+    fn read_optional<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Result<Option<T>> {
+        match self.read_bool()? {
+            true => Ok(Some(read(self)?)),
+            false => Ok(None),
+        }
+    }
+
+    fn read_vec<T>(&mut self, mut read: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let length = self.read_length()?;
+        (0..length).map(|_| read(self)).collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+}
+
+fn read_block_position(reader: &mut StateHistoryReader) -> Result<(u32, String)> {
+    Ok((reader.read_u32()?, reader.read_checksum256()?))
+}
+
+fn read_account_delta(reader: &mut StateHistoryReader) -> Result<()> {
+    reader.read_name()?;
+    reader.read_u64()?;
+    Ok(())
+}
+
+fn read_signature(reader: &mut StateHistoryReader) -> Result<()> {
+    let signature_type = reader.read_varuint32()?;
+    reader.read_bytes(SIGNATURE_LENGTH)?;
+    if signature_type == WEBAUTHN_SIGNATURE_INDEX {
+        reader.read_byte_vec()?;
+        reader.read_string()?;
+    };
+    Ok(())
+}
+
+fn read_partial_transaction(reader: &mut StateHistoryReader) -> Result<()> {
+    reader.read_variant_index("partial_transaction", &[PARTIAL_TRANSACTION_V0_INDEX])?;
+    reader.read_u32()?; // NOTE: expiration
+    reader.read_u16()?; // NOTE: ref_block_num
+    reader.read_u32()?; // NOTE: ref_block_prefix
+    reader.read_varuint32()?; // NOTE: max_net_usage_words
+    reader.read_u8()?; // NOTE: max_cpu_usage_ms
+    reader.read_varuint32()?; // NOTE: delay_sec
+    reader.read_vec(|reader| {
+        reader.read_u16()?;
+        reader.read_byte_vec()
+    })?;
+    reader.read_vec(read_signature)?;
+    reader.read_vec(|reader| reader.read_byte_vec())?;
+    Ok(())
+}
+
+fn read_action_receipt(
+    reader: &mut StateHistoryReader,
+    tx_id: &str,
+) -> Result<EosActionReceiptJson> {
+    reader.read_variant_index("action_receipt", &[ACTION_RECEIPT_V0_INDEX])?;
+    Ok(EosActionReceiptJson {
+        tx_id: tx_id.to_string(),
+        receiver: reader.read_name()?,
+        act_digest: reader.read_checksum256()?,
+        global_sequence: reader.read_u64()?,
+        recv_sequence: reader.read_u64()?,
+        auth_sequence: reader
+            .read_vec(|reader| Ok(AuthSequenceJson(reader.read_name()?, reader.read_u64()?)))?,
+        code_sequence: reader.read_varuint32()? as usize,
+        abi_sequence: reader.read_varuint32()? as usize,
+    })
+}
+
+fn read_action(reader: &mut StateHistoryReader) -> Result<EosActionJson> {
+    let account = reader.read_name()?;
+    let name = reader.read_name()?;
+    let authorization = reader.read_vec(|reader| {
+        Ok(AuthorizationJson {
+            actor: reader.read_name()?,
+            permission: reader.read_name()?,
+        })
+    })?;
+    let hex_data = hex::encode(reader.read_byte_vec()?);
+    Ok(EosActionJson {
+        name,
+        account,
+        data: serde_json::Value::String(hex_data.clone()),
+        hex_data: Some(hex_data),
+        authorization,
+        return_value_hex_data: None,
+    })
+}
+
+fn read_action_trace(
+    reader: &mut StateHistoryReader,
+    tx_id: &str,
+) -> Result<Option<(EosActionReceiptJson, EosActionJson)>> {
+    let version = reader.read_variant_index(
+        "action_trace",
+        &[ACTION_TRACE_V0_INDEX, ACTION_TRACE_V1_INDEX],
+    )?;
+    reader.read_varuint32()?; // NOTE: action_ordinal
+    reader.read_varuint32()?; // NOTE: creator_action_ordinal
+    let maybe_receipt = reader.read_optional(|reader| read_action_receipt(reader, tx_id))?;
+    reader.read_name()?; // NOTE: receiver, which the receipt also carries.
+    let mut action = read_action(reader)?;
+    reader.read_bool()?; // NOTE: context_free
+    reader.read_u64()?; // NOTE: elapsed
+    reader.read_string()?; // NOTE: console
+    reader.read_vec(read_account_delta)?;
+    reader.read_optional(|reader| reader.read_string())?; // NOTE: except
+    reader.read_optional(|reader| reader.read_u64())?; // NOTE: error_code
+    if version == ACTION_TRACE_V1_INDEX {
+        let return_value = reader.read_byte_vec()?;
+        if !return_value.is_empty() {
+            action.return_value_hex_data = Some(hex::encode(return_value));
+        }
+    };
+    // NOTE: Actions which failed or were never executed have no receipt, so are not in the block.
+    Ok(maybe_receipt.map(|receipt| (receipt, action)))
+}
+
+fn read_transaction_trace(
+    reader: &mut StateHistoryReader,
+) -> Result<Vec<(EosActionReceiptJson, EosActionJson)>> {
+    reader.read_variant_index("transaction_trace", &[TRANSACTION_TRACE_V0_INDEX])?;
+    let tx_id = reader.read_checksum256()?;
+    reader.read_u8()?; // NOTE: status
+    reader.read_u32()?; // NOTE: cpu_usage_us
+    reader.read_varuint32()?; // NOTE: net_usage_words
+    reader.read_u64()?; // NOTE: elapsed
+    reader.read_u64()?; // NOTE: net_usage
+    reader.read_bool()?; // NOTE: scheduled
+    let action_traces = reader.read_vec(|reader| read_action_trace(reader, &tx_id))?;
+    reader.read_optional(read_account_delta)?;
+    reader.read_optional(|reader| reader.read_string())?; // NOTE: except
+    reader.read_optional(|reader| reader.read_u64())?; // NOTE: error_code
+
+    // NOTE: A failed deferred transaction's actions are not in the block, so are skipped.
+    reader.read_optional(read_transaction_trace)?;
+    reader.read_optional(read_partial_transaction)?;
+    Ok(action_traces.into_iter().flatten().collect())
+}
+
+fn decode_transaction_traces(bytes: &[Byte]) -> Result<Vec<(EosActionReceiptJson, EosActionJson)>> {
+    let mut reader = StateHistoryReader::new(bytes);
+    let transaction_traces = reader.read_vec(read_transaction_trace)?;
+    match reader.is_empty() {
+        true => Ok(transaction_traces.into_iter().flatten().collect()),
+        false => Err(AppError::Custom(
+            "✘ Trailing bytes after state-history transaction traces!".to_string(),
+        )),
+    }
+}
+
+/// Decodes a state-history plugin `get_blocks_result_v0` message into the receipts & actions of
+/// the block it carries the traces of, sorted by global sequence.
+pub fn decode_state_history_result(bytes: &[Byte]) -> Result<StateHistoryBlockTraces> {
+    let mut reader = StateHistoryReader::new(bytes);
+    reader.read_variant_index("result", &[GET_BLOCKS_RESULT_V0_INDEX])?;
+    read_block_position(&mut reader)?; // NOTE: head
+    read_block_position(&mut reader)?; // NOTE: last_irreversible
+    let (block_num, block_id) = reader
+        .read_optional(read_block_position)?
+        .ok_or_else(|| AppError::Custom("✘ State-history result carries no block!".to_string()))?;
+    reader.read_optional(read_block_position)?; // NOTE: prev_block
+    reader.read_optional(|reader| reader.read_byte_vec())?; // NOTE: block
+    let traces = reader
+        .read_optional(|reader| reader.read_byte_vec())?
+        .ok_or_else(|| {
+            AppError::Custom(
+                "✘ State-history result carries no traces! Request them with `fetch_traces`."
+                    .to_string(),
+            )
+        })?;
+    reader.read_optional(|reader| reader.read_byte_vec())?; // NOTE: deltas
+    let mut pairs = decode_transaction_traces(traces)?;
+    pairs.sort_by_key(|(receipt_json, _)| receipt_json.global_sequence);
+    let (action_receipts, actions) = pairs.into_iter().unzip();
+    Ok(StateHistoryBlockTraces {
+        block_num: u64::from(block_num),
+        block_id,
+        action_receipts,
+        actions,
+    })
+}
+
+pub fn get_action_receipts_from_state_history_result(bytes: &[Byte]) -> Result<EosActionReceipts> {
+    decode_state_history_result(bytes)
+        .and_then(|block_traces| parse_action_receipt_jsons(&block_traces.action_receipts))
+}
+
+pub fn get_actions_from_state_history_result(bytes: &[Byte]) -> Result<EosActions> {
+    decode_state_history_result(bytes).and_then(|block_traces| {
+        block_traces
+            .actions
+            .iter()
+            .map(parse_eos_action_json)
+            .collect::<Result<EosActions>>()
+    })
+}
+
+fn decode_state_history_result_hex(hex: &str) -> Result<StateHistoryBlockTraces> {
+    let bytes: Bytes = hex::decode(strip_hex_prefix(hex))?;
+    decode_state_history_result(&bytes)
+}
+
+/// If the input carries a hex-encoded `state_history_result` in place of `action_receipts`,
+/// extracts the receipts from it. If it has no `action` or `actions` either, its `selector` picks
+/// them out.
+pub fn maybe_get_action_receipts_from_state_history_result(
+    mut input_json: EosInputJson,
+) -> Result<EosInputJson> {
+    let state_history_result = match input_json.state_history_result.take() {
+        None => return Ok(input_json),
+        Some(_)
+            if !input_json.action_receipts.is_empty()
+                || input_json.trace_api_block.is_some()
+                || input_json.get_actions_response.is_some() =>
+        {
+            return Err(AppError::Custom(
+                "✘ Input JSON must not contain a `state_history_result` as well as other action receipts!"
+                    .to_string(),
+            ))
+        }
+        Some(state_history_result) => state_history_result,
+    };
+    info!("✔ Getting action receipts from `state_history_result`...");
+    let block_traces = decode_state_history_result_hex(&state_history_result)?;
+    if !strip_hex_prefix(&input_json.block.block_id).eq_ignore_ascii_case(&block_traces.block_id) {
+        return Err(AppError::Custom(format!(
+            "✘ Block id mismatch between block & state-history result!\n✘ Block: {}\n✘ Result: {}",
+            input_json.block.block_id, block_traces.block_id
+        )));
+    };
+    put_action_receipts_and_maybe_selected_actions_in_input_json(
+        input_json,
+        block_traces.action_receipts,
+        block_traces.actions,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_merkle_tree::get_action_receipt_digests,
+        parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+        test_utils::{
+            get_sample_action_n, get_sample_action_receipts_n, get_sample_state_history_result_n,
+            get_sample_submission_json_n,
+        },
+        types::ReceiptSelectorJson,
+    };
+    use eos_chain::SerializeData;
+
+    #[test]
+    fn should_read_varuint32s() {
+        let bytes = [0x00, 0x7f, 0x80, 0x01, 0xff, 0xff, 0xff, 0xff, 0x0f];
+        let mut reader = StateHistoryReader::new(&bytes);
+        assert_eq!(reader.read_varuint32().unwrap(), 0);
+        assert_eq!(reader.read_varuint32().unwrap(), 127);
+        assert_eq!(reader.read_varuint32().unwrap(), 128);
+        assert_eq!(reader.read_varuint32().unwrap(), u32::MAX);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn should_fail_to_read_past_end_of_data() {
+        let bytes = [0x01, 0x02, 0x03];
+        let mut reader = StateHistoryReader::new(&bytes);
+        assert!(reader.read_u32().is_err());
+    }
+
+    #[test]
+    fn should_decode_state_history_result() {
+        // NOTE: The sample mixes `action_trace_v0` & `v1`, & has an action without a receipt.
+        let sample_json = get_sample_submission_json_n(1).unwrap();
+        let result =
+            decode_state_history_result(&get_sample_state_history_result_n(1).unwrap()).unwrap();
+        assert_eq!(result.block_num, 84756340);
+        assert_eq!(result.block_id, sample_json.block.block_id);
+        assert_eq!(
+            serde_json::to_value(&result.action_receipts).unwrap(),
+            serde_json::to_value(sort_action_receipt_jsons_by_global_sequence(
+                &sample_json.action_receipts
+            ))
+            .unwrap()
+        );
+        assert_eq!(result.actions.len(), 6);
+    }
+
+    #[test]
+    fn should_get_action_receipts_and_actions_from_state_history_result() {
+        let bytes = get_sample_state_history_result_n(1).unwrap();
+        let action_receipts = get_action_receipts_from_state_history_result(&bytes).unwrap();
+        let actions = get_actions_from_state_history_result(&bytes).unwrap();
+        assert_eq!(
+            get_action_receipt_digests(&action_receipts).unwrap(),
+            get_action_receipt_digests(&get_sample_action_receipts_n(1).unwrap()).unwrap()
+        );
+        let expected_action = get_sample_action_n(1).unwrap().to_serialize_data().unwrap();
+        assert!(actions
+            .iter()
+            .any(|action| action.to_serialize_data().unwrap() == expected_action));
+    }
+
+    #[test]
+    fn should_fail_to_decode_truncated_state_history_result() {
+        let bytes = get_sample_state_history_result_n(1).unwrap();
+        assert!(decode_state_history_result(&bytes[..bytes.len() - 8]).is_err());
+    }
+
+    #[test]
+    fn should_get_action_receipts_and_selected_action_from_state_history_result_in_input_json() {
+        let expected_result = get_sample_submission_json_n(1).unwrap();
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        input_json.action = None;
+        input_json.action_receipts = vec![];
+        input_json.state_history_result =
+            Some(hex::encode(get_sample_state_history_result_n(1).unwrap()));
+        input_json.selector = Some(ReceiptSelectorJson {
+            global_sequence: Some(584628427),
+            ..Default::default()
+        });
+        let result = maybe_get_action_receipts_from_state_history_result(input_json).unwrap();
+        let (action, expected_action) = (result.action.unwrap(), expected_result.action.unwrap());
+        assert!(result.state_history_result.is_none());
+        assert_eq!(result.action_receipts.len(), 6);
+        assert_eq!(action.name, expected_action.name);
+        assert_eq!(action.hex_data, expected_action.hex_data);
+    }
+
+    #[test]
+    fn should_fail_if_block_ids_differ_from_state_history_result() {
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        input_json.action_receipts = vec![];
+        input_json.block.block_id = "00".repeat(32);
+        input_json.state_history_result =
+            Some(hex::encode(get_sample_state_history_result_n(1).unwrap()));
+        assert!(maybe_get_action_receipts_from_state_history_result(input_json).is_err());
+    }
+}
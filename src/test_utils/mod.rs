@@ -1,16 +1,27 @@
 #![cfg(test)]
 use crate::{
+    build_merkle_tree::get_action_receipt_digests,
+    eos_merkle_utils::{
+        generate_merkle_proof, get_merkle_root_from_tree, get_merkle_tree, MerkleRoot,
+    },
     error::AppError,
-    generate_proof::generate_merkle_proof_from_action_receipts,
     parse_eos_action::parse_eos_action_json,
     parse_eos_action_receipts::parse_action_receipt_jsons,
     parse_eos_action_receipts::sort_action_receipts_by_global_sequence,
     parse_eos_block::parse_eos_block_json,
-    parse_input_json::parse_eos_input_json_string,
-    types::{EosActionReceipts, EosBlock, EosInputJson, MerkleProof, Result},
+    parse_input_json::{get_action_jsons_from_input_json, parse_eos_input_json_string},
+    proof_maker::{GenerateOptions, ProofMaker},
+    types::{
+        Bytes, EosActionReceipts, EosBlock, EosInputJson, GetActionsResponseJson, MerkleProof,
+        Output, Result, TraceApiBlockJson,
+    },
+    verify_output::parse_output_json_string,
 };
 use eos_chain::Action as EosAction;
-use std::{fs::read_to_string, path::Path};
+use std::{
+    fs::{read, read_to_string},
+    path::Path,
+};
 
 pub const NUM_SAMPLES: usize = 1;
 pub const MERKLE_PROOF_INDEX: u32 = 3;
@@ -29,6 +40,69 @@ pub fn get_sample_submission_json_n(n: usize) -> Result<EosInputJson> {
     parse_eos_input_json_string(&get_sample_submission_string_n(n)?)
 }
 
+pub fn get_sample_trace_api_block_json_n(n: usize) -> Result<TraceApiBlockJson> {
+    // NOTE: Synthesized from sample n. Only its `colateraldel` & `redeem` actions carry real data.
+    let path = format!("src/test_utils/sample-trace-api-block-{}.json", n);
+    Ok(serde_json::from_str(&read_to_string(path)?)?)
+}
+
+pub fn get_sample_get_actions_response_json_n(n: usize) -> Result<GetActionsResponseJson> {
+    // NOTE: Sample n's receipts as `get_actions` traces, some Hyperion-style, plus a duplicate &
+    // a trace from the next block.
+    let path = format!("src/test_utils/sample-get-actions-{}.json", n);
+    Ok(serde_json::from_str(&read_to_string(path)?)?)
+}
+
+pub fn get_sample_state_history_result_n(n: usize) -> Result<Bytes> {
+    // NOTE: A `get_blocks_result_v0` message packing the traces of sample n's block.
+    let path = format!("src/test_utils/sample-state-history-result-{}.bin", n);
+    Ok(read(path)?)
+}
+
+pub fn get_sample_batch_submission_string_n(n: usize) -> Result<String> {
+    // NOTE: Swaps the single `action` for all the actions in the sample block's first transaction.
+    let mut json: serde_json::Value = serde_json::from_str(&get_sample_submission_string_n(n)?)?;
+    json["actions"] = json["block"]["transactions"][0]["trx"]["transaction"]["actions"].clone();
+    json["action"] = serde_json::Value::Null;
+    Ok(json.to_string())
+}
+
+pub fn get_sample_block_header_state_string_n(n: usize) -> Result<String> {
+    // NOTE: Synthesized for sample n's block, re-signed by a test key since its real producer's
+    // key & header state aren't included. Its `header` carries that test signature.
+    let path = format!("src/test_utils/sample-block-header-state-{}.json", n);
+    Ok(read_to_string(path)?)
+}
+
+pub fn get_sample_producer_schedule_string_n(n: usize) -> Result<String> {
+    // NOTE: A `get_producer_schedule` response whose `active` schedule has the test key.
+    let path = format!("src/test_utils/sample-producer-schedule-{}.json", n);
+    Ok(read_to_string(path)?)
+}
+
+pub fn get_sample_later_block_ids_n(n: usize) -> Result<Vec<String>> {
+    // NOTE: Synthesized ids of the five blocks after sample n's, so block n + 6 is the anchor.
+    let path = format!("src/test_utils/sample-later-block-ids-{}.json", n);
+    Ok(serde_json::from_str(&read_to_string(path)?)?)
+}
+
+pub fn get_sample_signed_submission_string_n(n: usize) -> Result<String> {
+    let block_header_state: serde_json::Value =
+        serde_json::from_str(&get_sample_block_header_state_string_n(n)?)?;
+    let producer_schedule: serde_json::Value =
+        serde_json::from_str(&get_sample_producer_schedule_string_n(n)?)?;
+    let mut json: serde_json::Value = serde_json::from_str(&get_sample_submission_string_n(n)?)?;
+    json["block"]["producer_signature"] =
+        block_header_state["header"]["producer_signature"].clone();
+    json["block_header_state"] = block_header_state;
+    json["producer_schedule"] = producer_schedule["active"].clone();
+    Ok(json.to_string())
+}
+
+pub fn get_sample_signed_submission_json_n(n: usize) -> Result<EosInputJson> {
+    parse_eos_input_json_string(&get_sample_signed_submission_string_n(n)?)
+}
+
 pub fn get_sample_eos_block_n(n: usize) -> Result<EosBlock> {
     get_sample_submission_json_n(n).and_then(|json| parse_eos_block_json(&json.block))
 }
@@ -40,13 +114,59 @@ pub fn get_sample_action_receipts_n(n: usize) -> Result<EosActionReceipts> {
 }
 
 pub fn get_sample_action_n(n: usize) -> Result<EosAction> {
-    get_sample_submission_json_n(n).and_then(|json| parse_eos_action_json(&json.action))
+    get_sample_submission_json_n(n)
+        .and_then(|json| get_action_jsons_from_input_json(&json))
+        .and_then(|action_jsons| parse_eos_action_json(&action_jsons[0]))
+}
+
+pub fn get_merkle_digest_from_action_receipts(
+    action_receipts: &EosActionReceipts,
+) -> Result<MerkleRoot> {
+    get_action_receipt_digests(action_receipts)
+        .and_then(get_merkle_tree)
+        .map(|tree| get_merkle_root_from_tree(&tree))
 }
 
 pub fn get_sample_merkle_proof_n(n: usize) -> Result<MerkleProof> {
-    get_sample_action_receipts_n(n).and_then(|receipts| {
-        generate_merkle_proof_from_action_receipts(MERKLE_PROOF_INDEX, &receipts)
-    })
+    get_sample_action_receipts_n(n)
+        .and_then(|receipts| get_action_receipt_digests(&receipts))
+        .and_then(|leaves| {
+            generate_merkle_proof(MERKLE_PROOF_INDEX as usize, &get_merkle_tree(leaves)?)
+        })
+}
+
+pub fn get_output_string_from_input_json_string(input_json_string: String) -> Result<String> {
+    ProofMaker::generate_json_string(&input_json_string, GenerateOptions::default())
+}
+
+pub fn get_action_receipt_outputs_string_from_input_json_string(
+    input_json_string: String,
+) -> Result<String> {
+    ProofMaker::generate_json_string(
+        &input_json_string,
+        GenerateOptions {
+            all: true,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn get_multi_proof_output_string_from_input_json_string(
+    input_json_string: String,
+) -> Result<String> {
+    ProofMaker::generate_json_string(
+        &input_json_string,
+        GenerateOptions {
+            multi_proof: true,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn get_sample_output_n(n: usize) -> Result<Output> {
+    get_sample_submission_string_n(n)
+        .and_then(get_output_string_from_input_json_string)
+        .and_then(|output_string| parse_output_json_string(&output_string))
 }
 
 #[cfg(test)]
@@ -0,0 +1,316 @@
+use crate::{
+    error::AppError,
+    hex_utils::strip_hex_prefix,
+    history_api::parse_number_or_string_json,
+    parse_input_json::put_actions_matching_selector_in_input_json,
+    types::{
+        AuthSequenceJson, AuthorizationJson, EosActionJson, EosActionJsons, EosActionReceiptJson,
+        EosActionReceiptJsons, EosBlockJson, EosInputJson, ReceiptSelectorJson, Result,
+        TraceApiActionJson, TraceApiBlockJson,
+    },
+};
+
+fn convert_trace_api_action_to_action_receipt_json(
+    tx_id: &str,
+    action: &TraceApiActionJson,
+) -> Result<EosActionReceiptJson> {
+    let receipt = &action.receipt;
+    Ok(EosActionReceiptJson {
+        tx_id: tx_id.to_string(),
+        receiver: receipt.receiver.clone(),
+        act_digest: receipt.act_digest.clone(),
+        global_sequence: parse_number_or_string_json(&receipt.global_sequence)?,
+        recv_sequence: parse_number_or_string_json(&receipt.recv_sequence)?,
+        auth_sequence: receipt
+            .auth_sequence
+            .iter()
+            .map(|auth| {
+                Ok(AuthSequenceJson(
+                    auth.account.clone(),
+                    parse_number_or_string_json(&auth.sequence)?,
+                ))
+            })
+            .collect::<Result<Vec<AuthSequenceJson>>>()?,
+        code_sequence: receipt.code_sequence,
+        abi_sequence: receipt.abi_sequence,
+    })
+}
+
+fn convert_trace_api_action_to_action_json(action: &TraceApiActionJson) -> EosActionJson {
+    EosActionJson {
+        name: action.action.clone(),
+        account: action.account.clone(),
+        // NOTE: Prefer the ABI-decoded params for legibility, the hex data is what gets hashed.
+        data: action
+            .params
+            .clone()
+            .unwrap_or_else(|| serde_json::Value::String(action.data.clone())),
+        hex_data: Some(action.data.clone()),
+        authorization: action
+            .authorization
+            .iter()
+            .map(|auth| AuthorizationJson {
+                actor: auth.account.clone(),
+                permission: auth.permission.clone(),
+            })
+            .collect(),
+        return_value_hex_data: action
+            .return_value
+            .clone()
+            .filter(|return_value| !return_value.is_empty()),
+    }
+}
+
+fn get_sorted_receipt_and_action_jsons_from_trace_api_block(
+    trace_api_block: &TraceApiBlockJson,
+) -> Result<(EosActionReceiptJsons, EosActionJsons)> {
+    let mut pairs = trace_api_block
+        .transactions
+        .iter()
+        .flat_map(|tx| {
+            tx.actions.iter().map(move |action| {
+                Ok((
+                    convert_trace_api_action_to_action_receipt_json(&tx.id, action)?,
+                    convert_trace_api_action_to_action_json(action),
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    pairs.sort_by_key(|(receipt_json, _)| receipt_json.global_sequence);
+    Ok(pairs.into_iter().unzip())
+}
+
+pub fn get_action_receipt_jsons_from_trace_api_block(
+    trace_api_block: &TraceApiBlockJson,
+) -> Result<EosActionReceiptJsons> {
+    get_sorted_receipt_and_action_jsons_from_trace_api_block(trace_api_block)
+        .map(|(action_receipts, _)| action_receipts)
+}
+
+fn check_block_ids_match(
+    block_json: &EosBlockJson,
+    trace_api_block: &TraceApiBlockJson,
+) -> Result<()> {
+    match strip_hex_prefix(&block_json.block_id)
+        .eq_ignore_ascii_case(strip_hex_prefix(&trace_api_block.id))
+    {
+        true => Ok(()),
+        false => Err(AppError::Custom(format!(
+            "✘ Block id mismatch between block & traces!\n✘ Block: {}\n✘ Traces: {}",
+            block_json.block_id, trace_api_block.id
+        ))),
+    }
+}
+
+fn select_actions_from_trace_api_block(
+    mut input_json: EosInputJson,
+    trace_api_block: &TraceApiBlockJson,
+    selector: &ReceiptSelectorJson,
+) -> Result<EosInputJson> {
+    let (action_receipts, action_jsons) =
+        get_sorted_receipt_and_action_jsons_from_trace_api_block(trace_api_block)?;
+    input_json.trace_api_block = None;
+    put_actions_matching_selector_in_input_json(input_json, action_receipts, action_jsons, selector)
+}
+
+/// Builds the input for the proof maker from a block & its `trace_api_plugin` traces, with an
+/// action for each receipt matched by the `selector`, each pinned to its receipt.
+pub fn get_input_json_from_trace_api_block(
+    block_json: EosBlockJson,
+    trace_api_block: &TraceApiBlockJson,
+    selector: &ReceiptSelectorJson,
+) -> Result<EosInputJson> {
+    check_block_ids_match(&block_json, trace_api_block)?;
+    let input_json = EosInputJson {
+        block: block_json,
+        action: None,
+        actions: None,
+        selector: None,
+        selectors: None,
+        digest_scheme: None,
+        action_receipts: vec![],
+        trace_api_block: None,
+        get_actions_response: None,
+        state_history_result: None,
+        block_header_state: None,
+        producer_schedule: None,
+        later_block_ids: None,
+    };
+    select_actions_from_trace_api_block(input_json, trace_api_block, selector)
+}
+
+/// If the input carries a raw `trace_api_block` in place of `action_receipts`, extracts the
+/// receipts from it. If it has no `action` or `actions` either, its `selector` picks them out.
+pub fn maybe_get_action_receipts_from_trace_api_block(
+    mut input_json: EosInputJson,
+) -> Result<EosInputJson> {
+    let trace_api_block = match input_json.trace_api_block.take() {
+        None if input_json.action_receipts.is_empty() => {
+            return Err(AppError::Custom(
+                "✘ Input JSON must contain either `action_receipts` or a `trace_api_block`!"
+                    .to_string(),
+            ))
+        }
+        None => return Ok(input_json),
+        Some(_) if !input_json.action_receipts.is_empty() => {
+            return Err(AppError::Custom(
+                "✘ Input JSON must not contain both `action_receipts` & a `trace_api_block`!"
+                    .to_string(),
+            ))
+        }
+        Some(trace_api_block) => trace_api_block,
+    };
+    info!("✔ Getting action receipts from `trace_api_block`...");
+    check_block_ids_match(&input_json.block, &trace_api_block)?;
+    let has_actions = input_json.action.is_some() || input_json.actions.is_some();
+    match (has_actions, input_json.selector.clone()) {
+        (false, Some(selector)) => {
+            input_json.selector = None;
+            select_actions_from_trace_api_block(input_json, &trace_api_block, &selector)
+        }
+        _ => {
+            input_json.action_receipts =
+                get_action_receipt_jsons_from_trace_api_block(&trace_api_block)?;
+            Ok(input_json)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+        test_utils::{get_sample_submission_json_n, get_sample_trace_api_block_json_n},
+        types::NumberOrStringJson,
+    };
+
+    fn get_sample_selector(global_sequence: u64) -> ReceiptSelectorJson {
+        ReceiptSelectorJson {
+            global_sequence: Some(global_sequence),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_get_action_receipt_jsons_from_trace_api_block() {
+        // NOTE: The sample's first receipt has its sequences string-encoded, as nodeos does for
+        // those greater than `0xffffffff`.
+        let trace_api_block = get_sample_trace_api_block_json_n(1).unwrap();
+        assert_eq!(
+            trace_api_block.transactions[0].actions[0]
+                .receipt
+                .global_sequence,
+            NumberOrStringJson::String("584628422".to_string())
+        );
+        let result = get_action_receipt_jsons_from_trace_api_block(&trace_api_block).unwrap();
+        let expected_result = sort_action_receipt_jsons_by_global_sequence(
+            &get_sample_submission_json_n(1).unwrap().action_receipts,
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::to_value(&expected_result).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_get_input_json_with_single_action_from_trace_api_block() {
+        let sample_json = get_sample_submission_json_n(1).unwrap();
+        let result = get_input_json_from_trace_api_block(
+            sample_json.block.clone(),
+            &get_sample_trace_api_block_json_n(1).unwrap(),
+            &get_sample_selector(584628427),
+        )
+        .unwrap();
+        assert!(result.actions.is_none());
+        assert_eq!(
+            serde_json::to_value(&result.action).unwrap(),
+            serde_json::to_value(&sample_json.action).unwrap()
+        );
+        assert_eq!(result.selector, Some(get_sample_selector(584628427)));
+    }
+
+    #[test]
+    fn should_get_input_json_with_many_actions_from_trace_api_block() {
+        let sample_json = get_sample_submission_json_n(1).unwrap();
+        let selector = ReceiptSelectorJson {
+            tx_id: Some(sample_json.action_receipts[0].tx_id.clone()),
+            ..Default::default()
+        };
+        let result = get_input_json_from_trace_api_block(
+            sample_json.block,
+            &get_sample_trace_api_block_json_n(1).unwrap(),
+            &selector,
+        )
+        .unwrap();
+        assert!(result.action.is_none());
+        assert_eq!(result.actions.unwrap().len(), 6);
+        assert_eq!(result.selectors.unwrap()[0], get_sample_selector(584628422));
+    }
+
+    #[test]
+    fn should_fail_to_get_input_json_if_no_action_matches_selector() {
+        let sample_json = get_sample_submission_json_n(1).unwrap();
+        let result = get_input_json_from_trace_api_block(
+            sample_json.block,
+            &get_sample_trace_api_block_json_n(1).unwrap(),
+            &get_sample_selector(1337),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_get_action_receipts_and_selected_action_from_trace_api_block_in_input_json() {
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        let expected_result = get_sample_submission_json_n(1).unwrap();
+        input_json.action = None;
+        input_json.action_receipts = vec![];
+        input_json.trace_api_block = Some(get_sample_trace_api_block_json_n(1).unwrap());
+        input_json.selector = Some(get_sample_selector(584628427));
+        let result = maybe_get_action_receipts_from_trace_api_block(input_json).unwrap();
+        assert!(result.trace_api_block.is_none());
+        assert_eq!(
+            serde_json::to_value(&result.action).unwrap(),
+            serde_json::to_value(&expected_result.action).unwrap()
+        );
+        assert_eq!(
+            result.action_receipts.len(),
+            expected_result.action_receipts.len()
+        );
+    }
+
+    #[test]
+    fn should_only_get_action_receipts_from_trace_api_block_if_action_supplied() {
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        input_json.action_receipts = vec![];
+        input_json.trace_api_block = Some(get_sample_trace_api_block_json_n(1).unwrap());
+        let result = maybe_get_action_receipts_from_trace_api_block(input_json.clone()).unwrap();
+        assert_eq!(result.action_receipts.len(), 6);
+        assert_eq!(
+            serde_json::to_value(&result.action).unwrap(),
+            serde_json::to_value(&input_json.action).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_fail_if_input_json_has_both_or_neither_receipts_nor_trace_api_block() {
+        let mut input_json = get_sample_submission_json_n(1).unwrap();
+        input_json.trace_api_block = Some(get_sample_trace_api_block_json_n(1).unwrap());
+        assert!(maybe_get_action_receipts_from_trace_api_block(input_json.clone()).is_err());
+        input_json.trace_api_block = None;
+        input_json.action_receipts = vec![];
+        assert!(maybe_get_action_receipts_from_trace_api_block(input_json).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_get_input_json_if_block_ids_differ() {
+        let mut block_json = get_sample_submission_json_n(1).unwrap().block;
+        block_json.block_id = "00".repeat(32);
+        let result = get_input_json_from_trace_api_block(
+            block_json,
+            &get_sample_trace_api_block_json_n(1).unwrap(),
+            &get_sample_selector(584628427),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -1,45 +1,273 @@
 use crate::error::AppError;
-use eos_chain::ActionReceipt as EosActionReceipt;
+use eos_chain::{AccountName, Action as EosAction, ActionReceipt as EosActionReceipt};
 use std::result;
 
 pub type Byte = u8;
 pub type Bytes = Vec<Byte>;
+pub type Outputs = Vec<Output>;
+pub type ActionReceiptOutputs = Vec<ActionReceiptOutput>;
 pub type MerkleProof = Vec<String>;
+pub type EosActions = Vec<EosAction>;
+pub type MerkleProofs = Vec<MerkleProof>;
 pub type Result<T> = result::Result<T, AppError>;
+pub type EosActionJsons = Vec<EosActionJson>;
 pub type EosActionReceipts = Vec<EosActionReceipt>;
 pub type AuthSequenceJsons = Vec<AuthSequenceJson>;
 pub type AuthorizationJsons = Vec<AuthorizationJson>;
 pub type EosActionReceiptJsons = Vec<EosActionReceiptJson>;
+pub type ReceiptSelectorJsons = Vec<ReceiptSelectorJson>;
+pub type Checksum256Bytes = [Byte; 32];
+pub type TransactionProofOutputs = Vec<TransactionProofOutput>;
+pub type TransactionReceiptJsons = Vec<TransactionReceiptJson>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestScheme {
+    Legacy,
+    ReturnValue,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Output {
     pub tx_id: String,
     pub block_id: String,
+    #[serde(default)]
+    pub block_num: u64,
+    #[serde(default)]
+    pub timestamp: String,
     pub action_index: usize,
     pub action_digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_scheme: Option<DigestScheme>,
     pub action_proof: MerkleProof,
     pub serialized_action: String,
     pub action_json: EosActionJson,
     pub action_receipt_digest: String,
     pub serialized_action_receipt: String,
     pub action_receipt_json: EosActionReceiptJson,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_value_hex_data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_proof: Option<MerkleProof>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serialized_block_header: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleMultiProof {
+    pub num_leaves: usize,
+    pub leaf_indices: Vec<usize>,
+    pub nodes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MultiProofOutput {
+    pub block_id: String,
+    pub block_num: u64,
+    pub timestamp: String,
+    pub action_mroot: String,
+    pub serialized_actions: Vec<String>,
+    pub action_digests: Vec<String>,
+    pub digest_schemes: Vec<DigestScheme>,
+    pub action_receipt_digests: Vec<String>,
+    pub serialized_action_receipts: Vec<String>,
+    pub action_multi_proof: MerkleMultiProof,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_proof: Option<MerkleProof>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serialized_block_header: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActionReceiptOutput {
+    pub tx_id: String,
+    pub block_id: String,
+    pub block_num: u64,
+    pub timestamp: String,
+    pub action_index: usize,
+    pub action_proof: MerkleProof,
+    pub action_receipt_digest: String,
+    pub serialized_action_receipt: String,
+    pub action_receipt_json: EosActionReceiptJson,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_proof: Option<MerkleProof>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serialized_block_header: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionProofOutput {
+    pub tx_id: String,
+    pub block_id: String,
+    pub block_num: u64,
+    pub timestamp: String,
+    pub transaction_index: usize,
+    pub transaction_mroot: String,
+    pub transaction_proof: MerkleProof,
+    pub transaction_receipt_digest: String,
+    pub transaction_receipt_json: TransactionReceiptJson,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_proof: Option<MerkleProof>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serialized_block_header: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerificationOutput {
+    pub tx_id: String,
+    pub block_id: String,
+    pub action_index: usize,
+    pub action_mroot: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockroot: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EosInputJson {
     pub block: EosBlockJson,
-    pub action: EosActionJson,
+    pub action: Option<EosActionJson>,
+    pub actions: Option<EosActionJsons>,
+    pub selector: Option<ReceiptSelectorJson>,
+    pub selectors: Option<ReceiptSelectorJsons>,
+    pub digest_scheme: Option<DigestScheme>,
+    #[serde(default)]
     pub action_receipts: EosActionReceiptJsons,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_api_block: Option<TraceApiBlockJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub get_actions_response: Option<GetActionsResponseJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_history_result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_header_state: Option<BlockHeaderStateJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_schedule: Option<ProducerScheduleJson>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub later_block_ids: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateHistoryBlockTraces {
+    pub block_num: u64,
+    pub block_id: String,
+    pub action_receipts: EosActionReceiptJsons,
+    pub actions: EosActionJsons,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptSelectorJson {
+    pub receiver: Option<String>,
+    pub global_sequence: Option<u64>,
+    pub tx_id: Option<String>,
+    pub receipt_index: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetActionsResponseJson {
+    pub actions: Vec<GetActionsActionJson>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetActionsActionJson {
+    pub action_trace: ActionTraceJson,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ActionTraceJson {
-    pub receipt: EosActionReceiptJson,
+    pub trx_id: String,
+    #[serde(default)]
+    pub block_num: Option<NumberOrStringJson>,
+    pub receipt: ActionTraceReceiptJson,
+    pub act: EosActionJson,
+    #[serde(default)]
+    pub return_value_hex_data: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActionTraceReceiptJson {
+    pub receiver: String,
+    pub act_digest: String,
+    pub global_sequence: NumberOrStringJson,
+    pub recv_sequence: NumberOrStringJson,
+    pub auth_sequence: Vec<ActionTraceAuthSequenceJson>,
+    pub code_sequence: NumberOrStringJson,
+    pub abi_sequence: NumberOrStringJson,
+}
+
+// NOTE: Hyperion gives these as `{ "account": ..., "sequence": ... }` objects instead of tuples.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ActionTraceAuthSequenceJson {
+    Tuple(String, NumberOrStringJson),
+    Object {
+        account: String,
+        sequence: NumberOrStringJson,
+    },
+}
+
+// NOTE: History & trace endpoints may encode large integers as strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NumberOrStringJson {
+    Number(u64),
+    String(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiBlockJson {
+    pub id: String,
+    pub number: u64,
+    pub transactions: Vec<TraceApiTransactionJson>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiTransactionJson {
+    pub id: String,
+    pub actions: Vec<TraceApiActionJson>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiActionJson {
+    pub receiver: String,
+    pub account: String,
+    pub action: String,
+    pub authorization: Vec<TraceApiAuthorizationJson>,
+    pub data: String,
+    #[serde(default)]
+    pub params: Option<serde_json::Value>,
+    #[serde(default)]
+    pub return_value: Option<String>,
+    pub receipt: TraceApiActionReceiptJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiAuthorizationJson {
+    pub account: String,
+    pub permission: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiActionReceiptJson {
+    pub receiver: String,
+    pub act_digest: String,
+    pub global_sequence: NumberOrStringJson,
+    pub recv_sequence: NumberOrStringJson,
+    pub auth_sequence: Vec<TraceApiAuthSequenceJson>,
+    pub code_sequence: usize,
+    pub abi_sequence: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceApiAuthSequenceJson {
+    pub account: String,
+    pub sequence: NumberOrStringJson,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EosBlockJson {
     pub block_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_num: Option<u64>,
     pub timestamp: String,
     pub producer: String,
     pub confirmed: usize,
@@ -49,21 +277,92 @@ pub struct EosBlockJson {
     pub schedule_version: usize,
     pub new_producers: serde_json::Value,
     pub header_extensions: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<TransactionReceiptJsons>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionReceiptJson {
+    pub status: String,
+    pub cpu_usage_us: u32,
+    pub net_usage_words: u32,
+    pub trx: TransactionReceiptTrxJson,
+}
+
+// NOTE: A deferred transaction's receipt holds only its id, any other's the packed transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionReceiptTrxJson {
+    Id(String),
+    Packed(PackedTransactionJson),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PackedTransactionJson {
+    pub id: String,
+    pub signatures: Vec<String>,
+    pub compression: String,
+    pub packed_context_free_data: String,
+    pub packed_trx: String,
+}
+
+// NOTE: The subset of a `nodeos` `get_block_header_state` response the producer signs over.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockHeaderStateJson {
+    pub blockroot_merkle: IncrementalMerkleJson,
+    pub pending_schedule: PendingScheduleJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IncrementalMerkleJson {
+    #[serde(rename = "_active_nodes")]
+    pub active_nodes: Vec<String>,
+    #[serde(rename = "_node_count")]
+    pub node_count: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingScheduleJson {
+    pub schedule_hash: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProducerScheduleJson {
+    pub version: u32,
+    pub producers: Vec<ProducerKeyJson>,
+}
+
+// NOTE: Legacy schedules give each producer a `block_signing_key`, newer ones an `authority`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProducerKeyJson {
+    pub producer_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_signing_key: Option<String>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub authority: serde_json::Value,
+}
+
+#[derive(Clone, Debug)]
 pub struct EosBlock {
     pub block_id: Bytes,
-    pub previous: String,
-    pub producer: String,
+    pub block_num: u64,
+    pub timestamp: BlockTimestamp,
+    pub previous: Checksum256Bytes,
+    pub producer: AccountName,
     pub confirmed: usize,
-    pub action_mroot: String,
+    pub action_mroot: Checksum256Bytes,
     pub schedule_version: usize,
-    pub transaction_mroot: String,
+    pub transaction_mroot: Checksum256Bytes,
     pub new_producers: serde_json::Value,
     pub header_extensions: serde_json::Value,
 }
 
+// NOTE: The number of half-second slots since the start of the year 2000.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BlockTimestamp(pub u32);
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EosActionJson {
     pub name: String,
@@ -71,6 +370,8 @@ pub struct EosActionJson {
     pub data: serde_json::Value, // NOTE: Could be hex string, or contract data!
     pub hex_data: Option<String>,
     pub authorization: AuthorizationJsons,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_value_hex_data: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -13,7 +13,11 @@ retirement in a given block.
 
 Usage:
         eos_action_proof_maker [--help]
-        eos_action_proof_maker generate (<JSON> | --file=<PATH>)
+        eos_action_proof_maker generate (<JSON> | --file=<PATH>) [--trace-api-block=<PATH> | --get-actions=<PATH> | --state-history-result=<PATH>] [--block-header-state=<PATH> [--schedule=<PATH>] [--later-block-ids=<PATH>]] [--all | --multi-proof]
+        eos_action_proof_maker generate-tx-proof (<JSON> | --file=<PATH>) [--tx-id=<HEX>] [--block-header-state=<PATH> [--schedule=<PATH>] [--later-block-ids=<PATH>]]
+        eos_action_proof_maker verify (<JSON> | --file=<PATH>) (--action-mroot=<HEX> | --block=<PATH> | --blockroot=<HEX>)
+        eos_action_proof_maker serve [--bind=<ADDRESS>]
+        eos_action_proof_maker fetch --rpc-url=<URL> --block-num=<NUM> (--tx-id=<HEX> | --selector=<JSON>) [--multi-proof]
 
 Options:
 
@@ -25,16 +29,174 @@ Options:
                           hex-string digests forming the branch of the
                           merkle-tree.
 
+    --all               ❍ Generate a merkle-proof for _every_ action receipt in
+                          the block instead. The supplied JSON then needs only
+                          the `block` & its `action_receipts`. Returns an array
+                          of outputs, each with the receipt, its index & its
+                          merkle-proof.
+
+    --multi-proof       ❍ Generate a single compact multi-proof for all of the
+                          supplied actions instead of one proof per action.
+                          Sibling nodes shared between the actions' branches
+                          are included only once. Returns the serialized
+                          actions, their digests & digest schemes, & the
+                          receipts plus the multi-proof, all ordered by
+                          ascending receipt index.
+
+    generate-tx-proof   ❍ Command to generate a merkle-proof for each of the
+                          transaction receipts in the `transactions` of the
+                          block in the supplied JSON, tying it to the block's
+                          `transaction_mroot`. Returns an array of outputs,
+                          each with the receipt, its index & its merkle-proof,
+                          or just the one output if a `--tx-id` is given.
+
+    --trace-api-block=<path>
+                        ❍ Path to a file containing a raw `trace_api_plugin`
+                          `/v1/trace_api/get_block` response for the block. Its
+                          action receipts are used in place of the <JSON>'s
+                          `action_receipts`, & if the <JSON> has no `action`
+                          or `actions`, its `selector` picks them out of it.
+
+    --get-actions=<path>
+                        ❍ Path to a file containing a `history_plugin`
+                          `/v1/history/get_actions` or Hyperion response
+                          covering the block. Used as `--trace-api-block` is,
+                          with traces from other blocks ignored.
+
+    --state-history-result=<path>
+                        ❍ Path to a file containing a binary state-history
+                          plugin `get_blocks_result_v0` message for the block,
+                          as recorded from its websocket with `fetch_traces`
+                          set. Used as `--trace-api-block` is.
+
+    --schedule=<path>   ❍ Path to a file containing the producer schedule the
+                          block was produced under, either as a whole
+                          `/v1/chain/get_producer_schedule` response or just
+                          its `active` schedule. The block's
+                          `producer_signature` is then checked against its
+                          producer's key in it.
+
+    --block-header-state=<path>
+                        ❍ Path to a file containing the block's
+                          `/v1/chain/get_block_header_state` response, whose
+                          `blockroot_merkle` & pending `schedule_hash` the
+                          producer signature commits to.
+
+    --later-block-ids=<path>
+                        ❍ Path to a file containing a JSON array of the ids of
+                          the blocks after this one, up to but excluding a
+                          trusted anchor block. The block's id is then proven
+                          against the anchor's `blockroot_merkle` root, & the
+                          output carries that `block_proof` plus the serialized
+                          block header.
+
+    verify              ❍ Command to verify a previously generated proof. Takes
+                          the output JSON of the `generate` command & checks
+                          the action, its receipt & its merkle-proof against a
+                          trusted `action_mroot`, reporting which step fails.
+
+    serve               ❍ Command to start a local HTTP server so proofs can be
+                          generated & verified without paying the process
+                          startup cost each time. Endpoints:
+                          `POST /generate`       ➔ Takes the <JSON> described
+                                                   below, returns one output.
+                          `POST /generate_batch` ➔ Takes <JSON> with `actions`,
+                                                   returns an array of outputs.
+                          `POST /verify`         ➔ Takes an output, plus an
+                                                   `?action_mroot=<hex>` query
+                                                   parameter to verify it
+                                                   against.
+                          `GET /health`          ➔ Reports the server's status.
+                          `GET /metrics`         ➔ Reports request & error
+                                                   counts per endpoint.
+
+    fetch               ❍ Command to build the <JSON> described below by calling
+                          `/v1/chain/get_block` & `/v1/trace_api/get_block` on
+                          a `nodeos` RPC endpoint, then generate proof(s) from
+                          it as the `generate` command does. Every action whose
+                          receipt matches the tx id or selector is proven.
+
+    --rpc-url=<url>     ❍ The `nodeos` RPC endpoint to fetch from. It needs the
+                          `trace_api_plugin` enabled.
+
+    --block-num=<num>   ❍ The number of the block containing the action(s).
+
+    --tx-id=<hex>       ❍ Prove the actions of the transaction with this id, or
+                          for `generate-tx-proof`, only its receipt.
+
+    --selector=<json>   ❍ Prove the actions whose receipts match this selector,
+                          as described for <JSON> below, eg:
+                          '{\"receiver\":\"ptokensbtc1a\"}'
+
+    --bind=<address>    ❍ The address for the server to listen on.
+                          [default: 127.0.0.1:8080]
+
+    --action-mroot=<hex>
+                        ❍ A trusted `action_mroot` to verify a proof against.
+
+    --block=<path>      ❍ Path to a file containing a trusted EOS block header
+                          JSON whose `action_mroot` to verify a proof against.
+                          Its `block_id` is first checked against the header.
+
+    --blockroot=<hex>   ❍ The trusted `blockroot_merkle` root of a later anchor
+                          block to verify a proof's `block_proof` against. The
+                          `action_mroot` in its serialized block header is then
+                          used to verify the rest of the proof.
+
     --file=<path>       ❍ Path to a file containg a JSON of an EOS block and all
                           its `action_receipts` & the action you desire a proof
-                          for.
+                          for. Pass `-` to read the JSON from stdin instead.
+                          Gzip or zstd compressed input is also accepted.
 
     <JSON>              ❍ A valid JSON string of an object containing keys:
-                          `block`           ➔ The EOS block header.
+                          `block`           ➔ The EOS block header. For the
+                                              `generate-tx-proof` command, the
+                                              whole `get_block` response, with
+                                              its `transactions`.
                           `action`          ➔ The action you want a proof for.
+                                              Include its return value as
+                                              `return_value_hex_data` if it
+                                              returned one.
+                          `actions`         ➔ Or, instead of `action`, an array
+                                              of actions from the same block to
+                                              make proofs for in one go. An
+                                              array of outputs is returned.
+                          `selector`        ➔ Optional. Picks which receipt to
+                                              prove if the action's digest
+                                              matches several. An object with
+                                              any of `receiver`,
+                                              `global_sequence`, `tx_id` or
+                                              `receipt_index`.
+                          `selectors`       ➔ Optional. With `actions`, an
+                                              array of one `selector` per
+                                              action.
+                          `digest_scheme`   ➔ Optional. Either `legacy` or
+                                              `return_value` to force how
+                                              action digests are calculated.
                           `action_receipts` ➔ An array of all the action
                                               receipts pertaining to the block
                                               in question.
+                          `trace_api_block` ➔ Or, instead of `action_receipts`,
+                                              a raw `trace_api_plugin` block.
+                          `get_actions_response`
+                                            ➔ Or, instead of either, a history
+                                              `get_actions` response.
+                          `state_history_result`
+                                            ➔ Or a hex-encoded binary
+                                              state-history result.
+                          `producer_schedule`
+                                            ➔ Optional. A producer schedule
+                                              to verify the block's
+                                              `producer_signature` against.
+                          `block_header_state`
+                                            ➔ Needed with `producer_schedule`
+                                              or `later_block_ids`. The
+                                              block's header state.
+                          `later_block_ids` ➔ Optional. The ids of the blocks
+                                              up to a trusted anchor block to
+                                              prove this block against.
+                          Or, for the `verify` command, the output JSON of the
+                          `generate` command.
 
     <PATH>              ❍ Path to a file containing the valid <JSON> string
                           described above.
@@ -1,27 +1,14 @@
 use crate::{
-    eos_merkle_utils::get_merkle_digest,
+    eos_merkle_utils::get_merkle_root_from_tree,
     error::AppError,
     state::State,
-    types::{Bytes, EosActionReceipts, Result},
+    types::{Checksum256Bytes, Result},
 };
 
-use eos_chain::Digest;
-
-pub fn get_merkle_digest_from_action_receipts(
-    action_receipts: &EosActionReceipts,
-) -> Result<Bytes> {
-    Ok(get_merkle_digest(
-        action_receipts
-            .iter()
-            .map(|receipt| Ok(receipt.digest()?.as_bytes().to_vec()))
-            .collect::<Result<_>>()?,
-    ))
-}
-
-fn check_merkle_digest(digest: &Bytes, action_mroot_hex: &String) -> Result<()> {
+fn check_merkle_digest(digest: &Checksum256Bytes, action_mroot: &Checksum256Bytes) -> Result<()> {
     debug!("Digest      : {}", hex::encode(digest));
-    debug!("Action Mroot: {}", action_mroot_hex);
-    match &hex::decode(action_mroot_hex)? == digest {
+    debug!("Action Mroot: {}", hex::encode(action_mroot));
+    match action_mroot == digest {
         true => Ok(()),
         false => Err(AppError::Custom(format!(
             "✘ Error validating action receipts!\n{}",
@@ -33,8 +20,8 @@ fn check_merkle_digest(digest: &Bytes, action_mroot_hex: &String) -> Result<()>
 pub fn validate_action_receipt_merkle_root(state: State) -> Result<State> {
     info!("✔ Validating action-receipts merkle root...");
     state
-        .get_eos_action_receipts()
-        .and_then(get_merkle_digest_from_action_receipts)
+        .get_merkle_tree()
+        .map(get_merkle_root_from_tree)
         .and_then(|digest| check_merkle_digest(&digest, &state.get_eos_block()?.action_mroot))
         .map(|_| {
             info!("✔ Receipts root is valid!");
@@ -45,7 +32,10 @@ pub fn validate_action_receipt_merkle_root(state: State) -> Result<State> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{get_sample_action_receipts_n, get_sample_eos_block_n, NUM_SAMPLES};
+    use crate::test_utils::{
+        get_merkle_digest_from_action_receipts, get_sample_action_receipts_n,
+        get_sample_eos_block_n, NUM_SAMPLES,
+    };
 
     #[test]
     fn should_validate_digest_for_sample_blocks() {
@@ -56,7 +46,7 @@ mod tests {
                 let expected_result = get_sample_eos_block_n(i + 1).unwrap().action_mroot;
                 let action_receipts = get_sample_action_receipts_n(i + 1).unwrap();
                 let result = get_merkle_digest_from_action_receipts(&action_receipts).unwrap();
-                assert_eq!(hex::encode(result), expected_result);
+                assert_eq!(result, expected_result);
             })
             .for_each(drop);
     }
@@ -67,11 +57,11 @@ mod tests {
             .iter()
             .enumerate()
             .map(|(i, _)| {
-                let action_mroot_hex = get_sample_eos_block_n(i + 1).unwrap().action_mroot;
+                let action_mroot = get_sample_eos_block_n(i + 1).unwrap().action_mroot;
                 let action_receipts = get_sample_action_receipts_n(i + 1).unwrap();
                 let valid_digests =
                     get_merkle_digest_from_action_receipts(&action_receipts).unwrap();
-                if let Err(e) = check_merkle_digest(&valid_digests, &action_mroot_hex) {
+                if let Err(e) = check_merkle_digest(&valid_digests, &action_mroot) {
                     panic!("Should validate valid merkle digest: {}", e);
                 }
             })
@@ -80,11 +70,11 @@ mod tests {
 
     #[test]
     fn should_err_when_validating_invalid_merkle_digest() {
-        let wrong_action_mroot_hex = get_sample_eos_block_n(1).unwrap().transaction_mroot;
+        let wrong_action_mroot = get_sample_eos_block_n(1).unwrap().transaction_mroot;
         let action_receipts = get_sample_action_receipts_n(1).unwrap();
         let valid_merkle_digests =
             get_merkle_digest_from_action_receipts(&action_receipts).unwrap();
-        if let Ok(_) = check_merkle_digest(&valid_merkle_digests, &wrong_action_mroot_hex) {
+        if let Ok(_) = check_merkle_digest(&valid_merkle_digests, &wrong_action_mroot) {
             panic!("Should NOT validate invalid merkle digest!");
         }
     }
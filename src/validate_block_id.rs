@@ -0,0 +1,90 @@
+use crate::{
+    error::AppError,
+    get_block_id::get_block_id_from_block_header_json,
+    parse_eos_block::parse_eos_block_json,
+    state::State,
+    types::{Bytes, EosBlock, EosBlockJson, Result},
+};
+
+fn check_block_id(computed_block_id: &Bytes, block_id: &Bytes) -> Result<()> {
+    debug!("Computed block id: {}", hex::encode(computed_block_id));
+    debug!("Supplied block id: {}", hex::encode(block_id));
+    match computed_block_id == block_id {
+        true => Ok(()),
+        false => Err(AppError::Custom(format!(
+            "✘ Error validating block!\n✘ Block id does NOT match its header!\n✘ Supplied: {}\n✘ Computed: {}",
+            hex::encode(block_id),
+            hex::encode(computed_block_id)
+        ))),
+    }
+}
+
+/// Parses a block header JSON, checking its `block_id` is the hash of that header.
+pub fn parse_and_validate_eos_block_json(block_json: &EosBlockJson) -> Result<EosBlock> {
+    let eos_block = parse_eos_block_json(block_json)?;
+    check_block_id(
+        &get_block_id_from_block_header_json(block_json)?,
+        &eos_block.block_id,
+    )?;
+    Ok(eos_block)
+}
+
+pub fn validate_block_id(state: State) -> Result<State> {
+    info!("✔ Validating block id against block header...");
+    state
+        .get_eos_input_json()
+        .and_then(|json| get_block_id_from_block_header_json(&json.block))
+        .and_then(|computed_block_id| {
+            check_block_id(&computed_block_id, &state.get_eos_block()?.block_id)
+        })
+        .map(|_| {
+            info!("✔ Block id is valid!");
+            state
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_eos_block::parse_eos_block_json_and_put_in_state,
+        test_utils::get_sample_submission_json_n,
+    };
+
+    fn get_state_with_block(action_mroot: Option<String>) -> Result<State> {
+        let mut input_json = get_sample_submission_json_n(1)?;
+        if let Some(action_mroot) = action_mroot {
+            input_json.block.action_mroot = action_mroot;
+        };
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json))
+            .and_then(parse_eos_block_json_and_put_in_state)
+    }
+
+    #[test]
+    fn should_validate_block_id() {
+        let state = get_state_with_block(None).unwrap();
+        if let Err(e) = validate_block_id(state) {
+            panic!("Block id should be valid: {}", e);
+        }
+    }
+
+    #[test]
+    fn should_fail_to_validate_block_id_of_forged_action_mroot() {
+        let forged_action_mroot = "00".repeat(32);
+        let state = get_state_with_block(Some(forged_action_mroot)).unwrap();
+        let err = validate_block_id(state).unwrap_err().to_string();
+        assert!(err.contains("Block id does NOT match its header"));
+    }
+
+    #[test]
+    fn should_parse_and_validate_eos_block_json() {
+        let mut block_json = get_sample_submission_json_n(1).unwrap().block;
+        let eos_block = parse_and_validate_eos_block_json(&block_json).unwrap();
+        assert_eq!(hex::encode(eos_block.action_mroot), block_json.action_mroot);
+        block_json.action_mroot = "00".repeat(32);
+        assert!(parse_and_validate_eos_block_json(&block_json).is_err());
+        block_json.action_mroot = "00".repeat(31);
+        assert!(parse_and_validate_eos_block_json(&block_json).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use crate::{
+    error::AppError,
+    get_block_id::{get_block_header_digest, serialize_public_key, serialize_signature},
+    hex_utils::decode_checksum256,
+    incremental_merkle::IncrementalMerkle,
+    state::State,
+    types::{
+        BlockHeaderStateJson, Byte, Bytes, EosBlockJson, EosInputJson, ProducerKeyJson,
+        ProducerScheduleJson, Result,
+    },
+};
+use bitcoin_hashes::{sha256, Hash};
+use secp256k1::{recover, Message, RecoveryId, Signature};
+
+const K1_KEY_TYPE: Byte = 0;
+// NOTE: A legacy `block_signing_key` is equivalent to an authority of that key with weight 1.
+const LEGACY_KEY_WEIGHT: u16 = 1;
+const LEGACY_THRESHOLD: u32 = 1;
+
+struct ProducerAuthority {
+    threshold: u32,
+    keys: Vec<(Bytes, u16)>,
+}
+
+fn get_invalid_signature_err(signature: &str) -> AppError {
+    AppError::Custom(format!("✘ Invalid producer signature: {}", signature))
+}
+
+fn decode_k1_signature(signature: &str) -> Result<(Signature, RecoveryId)> {
+    let signature_bytes = match serialize_signature(signature)? {
+        bytes if bytes[0] == K1_KEY_TYPE => bytes[1..].to_vec(),
+        _ => return Err(get_invalid_signature_err(signature)),
+    };
+    // NOTE: The first byte is the recovery id plus 27, plus 4 more if the key is compressed.
+    let recovery_id = match signature_bytes[0] {
+        byte @ 27..=30 => byte - 27,
+        byte @ 31..=34 => byte - 31,
+        _ => return Err(get_invalid_signature_err(signature)),
+    };
+    Ok((
+        Signature::parse_slice(&signature_bytes[1..])?,
+        RecoveryId::parse(recovery_id)?,
+    ))
+}
+
+/// Computes the digest a producer signs, which commits to the block header, the merkle root of
+/// all prior block ids & the hash of the pending producer schedule.
+pub fn get_producer_signature_digest(
+    block_json: &EosBlockJson,
+    block_header_state: &BlockHeaderStateJson,
+) -> Result<Bytes> {
+    let header_blockroot_digest = sha256::Hash::hash(
+        &[
+            get_block_header_digest(block_json)?,
+            IncrementalMerkle::from_json(&block_header_state.blockroot_merkle)?
+                .get_root()
+                .to_vec(),
+        ]
+        .concat(),
+    );
+    let schedule_hash = &block_header_state.pending_schedule.schedule_hash;
+    let schedule_hash = decode_checksum256(schedule_hash).map_err(|_| {
+        AppError::Custom(format!(
+            "✘ Invalid block header state `schedule_hash`: {}",
+            schedule_hash
+        ))
+    })?;
+    Ok(sha256::Hash::hash(&[&header_blockroot_digest[..], &schedule_hash].concat()).to_vec())
+}
+
+/// Recovers the signer's public key, serialized as a K1 key type followed by its 33 bytes.
+pub fn recover_public_key_from_signature(digest: &[Byte], signature: &str) -> Result<Bytes> {
+    let (signature, recovery_id) = decode_k1_signature(signature)?;
+    let public_key = recover(&Message::parse_slice(digest)?, &signature, &recovery_id)?;
+    Ok([
+        vec![K1_KEY_TYPE],
+        public_key.serialize_compressed().to_vec(),
+    ]
+    .concat())
+}
+
+fn get_invalid_authority_err(producer: &str) -> AppError {
+    AppError::Custom(format!(
+        "✘ Invalid `block_signing_authority_v0` for producer `{}`!",
+        producer
+    ))
+}
+
+fn parse_block_signing_authority(
+    producer: &str,
+    authority: &serde_json::Value,
+) -> Result<ProducerAuthority> {
+    // NOTE: A `block_signing_authority_v0` is given as `[0, {threshold, keys: [{key, weight}]}]`,
+    // with a `uint32` threshold & `uint16` weights.
+    let threshold = authority[1]["threshold"]
+        .as_u64()
+        .and_then(|threshold| u32::try_from(threshold).ok());
+    match (
+        authority[0].as_u64(),
+        threshold,
+        authority[1]["keys"].as_array(),
+    ) {
+        // NOTE: A zero threshold would be satisfied by any signature at all.
+        (Some(0), Some(threshold), Some(keys)) if threshold > 0 => {
+            let keys = keys
+                .iter()
+                .map(|key| {
+                    let weight = key["weight"]
+                        .as_u64()
+                        .and_then(|weight| u16::try_from(weight).ok());
+                    match (key["key"].as_str(), weight) {
+                        (Some(public_key), Some(weight)) => {
+                            Ok((serialize_public_key(public_key)?, weight))
+                        }
+                        _ => Err(get_invalid_authority_err(producer)),
+                    }
+                })
+                .collect::<Result<Vec<(Bytes, u16)>>>()?;
+            // NOTE: As in `nodeos`, keys must be unique & sorted, lest one key be counted twice.
+            match keys.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+                true => Ok(ProducerAuthority { threshold, keys }),
+                false => Err(get_invalid_authority_err(producer)),
+            }
+        }
+        _ => Err(get_invalid_authority_err(producer)),
+    }
+}
+
+fn get_producer_authority(producer_key: &ProducerKeyJson) -> Result<ProducerAuthority> {
+    match (
+        &producer_key.block_signing_key,
+        producer_key.authority.is_null(),
+    ) {
+        (_, false) => {
+            parse_block_signing_authority(&producer_key.producer_name, &producer_key.authority)
+        }
+        (Some(block_signing_key), true) => Ok(ProducerAuthority {
+            threshold: LEGACY_THRESHOLD,
+            keys: vec![(serialize_public_key(block_signing_key)?, LEGACY_KEY_WEIGHT)],
+        }),
+        (None, true) => Err(AppError::Custom(format!(
+            "✘ Producer `{}` has neither a `block_signing_key` nor an `authority`!",
+            producer_key.producer_name
+        ))),
+    }
+}
+
+fn get_producer_authority_from_schedule(
+    producer_schedule: &ProducerScheduleJson,
+    producer: &str,
+) -> Result<ProducerAuthority> {
+    producer_schedule
+        .producers
+        .iter()
+        .find(|producer_key| producer_key.producer_name == producer)
+        .ok_or_else(|| {
+            AppError::Custom(format!(
+                "✘ Producer `{}` is not in the producer schedule!",
+                producer
+            ))
+        })
+        .and_then(get_producer_authority)
+}
+
+fn get_signing_weight(authority: &ProducerAuthority, recovered_key: &[Byte]) -> Result<u32> {
+    authority
+        .keys
+        .iter()
+        .filter(|(key, _)| key == recovered_key)
+        .try_fold(0u32, |total, (_, weight)| {
+            total.checked_add(u32::from(*weight))
+        })
+        .ok_or_else(|| AppError::Custom("✘ Producer authority weight overflowed!".to_string()))
+}
+
+fn check_schedule_version(
+    producer_schedule: &ProducerScheduleJson,
+    block_json: &EosBlockJson,
+) -> Result<()> {
+    match producer_schedule.version as usize == block_json.schedule_version {
+        true => Ok(()),
+        false => Err(AppError::Custom(format!(
+            "✘ Producer schedule version {} does not match the block's `schedule_version` {}!",
+            producer_schedule.version, block_json.schedule_version
+        ))),
+    }
+}
+
+fn validate_producer_signature(
+    input_json: &EosInputJson,
+    producer_schedule: &ProducerScheduleJson,
+) -> Result<()> {
+    info!("✔ Validating producer signature against producer schedule...");
+    let block_json = &input_json.block;
+    let signature = block_json.producer_signature.as_ref().ok_or_else(|| {
+        AppError::Custom("✘ Block has no `producer_signature` to validate!".to_string())
+    })?;
+    let block_header_state = input_json.block_header_state.as_ref().ok_or_else(|| {
+        AppError::Custom(
+            "✘ A `block_header_state` is needed to validate the producer signature!".to_string(),
+        )
+    })?;
+    check_schedule_version(producer_schedule, block_json)?;
+    let authority = get_producer_authority_from_schedule(producer_schedule, &block_json.producer)?;
+    let digest = get_producer_signature_digest(block_json, block_header_state)?;
+    let recovered_key = recover_public_key_from_signature(&digest, signature)?;
+    debug!("Recovered producer key: {}", hex::encode(&recovered_key));
+    let signing_weight = get_signing_weight(&authority, &recovered_key)?;
+    match signing_weight >= authority.threshold {
+        true => {
+            info!("✔ Producer signature is valid!");
+            Ok(())
+        }
+        false => Err(AppError::Custom(format!(
+            "✘ Error validating block!\n✘ Producer signature was NOT made by `{}`'s key!\n✘ Recovered key: {}\n✘ Key weight {} is below the authority's threshold {}!",
+            block_json.producer,
+            hex::encode(&recovered_key),
+            signing_weight,
+            authority.threshold
+        ))),
+    }
+}
+
+pub fn maybe_validate_producer_signature(state: State) -> Result<State> {
+    let input_json = state.get_eos_input_json()?;
+    let result = match &input_json.producer_schedule {
+        Some(producer_schedule) => validate_producer_signature(input_json, producer_schedule),
+        None => {
+            info!("✔ No producer schedule supplied ∴ skipping producer signature validation...");
+            Ok(())
+        }
+    };
+    result.map(|_| state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        proof_maker::ProofMaker,
+        test_utils::{get_sample_signed_submission_json_n, get_sample_submission_json_n},
+    };
+
+    const SAMPLE_PRODUCER_KEY: &str = "PUB_K1_5mmxPtiRjPcENeUrwAmnTekRh9HwC7p6Pp2EHVPGQfZRdjLRBD";
+    const SAMPLE_OTHER_KEY: &str = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63";
+
+    fn validate_input_json(input_json: EosInputJson) -> Result<State> {
+        State::init()
+            .and_then(|state| state.add_eos_input_json(input_json))
+            .and_then(maybe_validate_producer_signature)
+    }
+
+    #[test]
+    fn should_recover_producer_key_from_signature() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let digest = get_producer_signature_digest(
+            &input_json.block,
+            input_json.block_header_state.as_ref().unwrap(),
+        )
+        .unwrap();
+        let result = recover_public_key_from_signature(
+            &digest,
+            input_json.block.producer_signature.as_ref().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(result, serialize_public_key(SAMPLE_PRODUCER_KEY).unwrap());
+    }
+
+    #[test]
+    fn should_decode_real_producer_signature() {
+        let block_json = get_sample_submission_json_n(1).unwrap().block;
+        assert!(decode_k1_signature(&block_json.producer_signature.unwrap()).is_ok());
+        let bad_checksum = "SIG_K1_JuVUXgJ5HUKZBK6jmgBy1qagtSCgb2y5JyWGh5QgD6ALr1DvPU8CrtXLXTgDa29cMDUWCxCU7eCwiEEEnbTmAxLxiBTnsz";
+        assert!(decode_k1_signature(bad_checksum).is_err());
+    }
+
+    #[test]
+    fn should_validate_producer_signature() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        if let Err(e) = validate_input_json(input_json) {
+            panic!("Producer signature should be valid: {}", e);
+        }
+    }
+
+    #[test]
+    fn should_skip_producer_signature_validation_without_schedule() {
+        let input_json = get_sample_submission_json_n(1).unwrap();
+        assert!(validate_input_json(input_json).is_ok());
+    }
+
+    #[test]
+    fn should_fail_to_validate_signature_by_another_key() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let producer_schedule = input_json.producer_schedule.as_mut().unwrap();
+        producer_schedule.producers[1].authority = serde_json::json!([0, {
+            "threshold": 1,
+            "keys": [{ "key": "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV", "weight": 1 }],
+        }]);
+        let err = validate_input_json(input_json).unwrap_err().to_string();
+        assert!(err.contains("Producer signature was NOT made by `evilproducer`'s key"));
+    }
+
+    #[test]
+    fn should_validate_signature_by_legacy_block_signing_key() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let producer_schedule = input_json.producer_schedule.as_mut().unwrap();
+        producer_schedule.producers[1].authority = serde_json::Value::Null;
+        producer_schedule.producers[1].block_signing_key = Some(SAMPLE_PRODUCER_KEY.to_string());
+        if let Err(e) = validate_input_json(input_json) {
+            panic!("Legacy block signing key should be valid: {}", e);
+        }
+    }
+
+    #[test]
+    fn should_only_validate_signature_if_key_weight_meets_authority_threshold() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let with_authority = |threshold: u64, weight: u64| {
+            let mut input_json = input_json.clone();
+            input_json.producer_schedule.as_mut().unwrap().producers[1].authority = serde_json::json!([0, {
+                "threshold": threshold,
+                "keys": [{ "key": SAMPLE_PRODUCER_KEY, "weight": weight }],
+            }]);
+            validate_input_json(input_json)
+        };
+        assert!(with_authority(2, 2).is_ok());
+        let err = with_authority(2, 1).unwrap_err().to_string();
+        assert!(err.contains("Key weight 1 is below the authority's threshold 2"));
+        assert!(with_authority(0, 0).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_validate_signature_if_authority_weights_or_threshold_are_out_of_range() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let with_authority = |threshold: u64, weight: u64| {
+            let mut input_json = input_json.clone();
+            input_json.producer_schedule.as_mut().unwrap().producers[1].authority = serde_json::json!([0, {
+                "threshold": threshold,
+                "keys": [{ "key": SAMPLE_PRODUCER_KEY, "weight": weight }],
+            }]);
+            validate_input_json(input_json)
+        };
+        assert!(with_authority(1, u16::MAX as u64).is_ok());
+        let err = with_authority(1, u16::MAX as u64 + 1)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid `block_signing_authority_v0`"));
+        assert!(with_authority(u32::MAX as u64 + 1, 1).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_validate_signature_if_authority_keys_are_duplicated_or_unsorted() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let with_keys = |keys: &[&str]| {
+            let mut input_json = input_json.clone();
+            input_json.producer_schedule.as_mut().unwrap().producers[1].authority = serde_json::json!([0, {
+                "threshold": 2,
+                "keys": keys
+                    .iter()
+                    .map(|key| serde_json::json!({ "key": key, "weight": 1 }))
+                    .collect::<Vec<_>>(),
+            }]);
+            validate_input_json(input_json)
+        };
+        let err = with_keys(&[SAMPLE_PRODUCER_KEY, SAMPLE_PRODUCER_KEY])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid `block_signing_authority_v0`"));
+        let mut keys = [SAMPLE_PRODUCER_KEY, SAMPLE_OTHER_KEY];
+        keys.sort_by_key(|key| serialize_public_key(key).unwrap());
+        let err = with_keys(&keys).unwrap_err().to_string();
+        assert!(err.contains("Key weight 1 is below the authority's threshold 2"));
+        keys.reverse();
+        let err = with_keys(&keys).unwrap_err().to_string();
+        assert!(err.contains("Invalid `block_signing_authority_v0`"));
+    }
+
+    #[test]
+    fn should_fail_to_validate_signature_over_different_header_state() {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let block_header_state = input_json.block_header_state.as_mut().unwrap();
+        block_header_state.pending_schedule.schedule_hash = "00".repeat(32);
+        assert!(validate_input_json(input_json).is_err());
+    }
+
+    #[test]
+    fn should_fail_to_validate_signature_without_header_state_or_matching_schedule() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let mut missing_state_json = input_json.clone();
+        missing_state_json.block_header_state = None;
+        assert!(validate_input_json(missing_state_json).is_err());
+        let mut wrong_version_json = input_json.clone();
+        wrong_version_json
+            .producer_schedule
+            .as_mut()
+            .unwrap()
+            .version += 1;
+        assert!(validate_input_json(wrong_version_json).is_err());
+        let mut unscheduled_producer_json = input_json;
+        unscheduled_producer_json
+            .producer_schedule
+            .as_mut()
+            .unwrap()
+            .producers
+            .remove(1);
+        assert!(validate_input_json(unscheduled_producer_json).is_err());
+    }
+
+    #[test]
+    fn should_generate_proof_from_block_with_validated_producer_signature() {
+        let input_json = get_sample_signed_submission_json_n(1).unwrap();
+        let output = ProofMaker::generate(&input_json).unwrap();
+        let expected_output =
+            ProofMaker::generate(&get_sample_submission_json_n(1).unwrap()).unwrap();
+        assert_eq!(output.action_proof, expected_output.action_proof);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    error::AppError,
+    types::{Byte, Bytes, Result},
+};
+
+// NOTE: Seven bits per byte, so a `u32` takes at most five.
+const MAX_VARUINT32_NUM_BYTES: usize = 5;
+
+/// Serializes a value as the LEB128 `varuint32` which EOSIO prefixes lengths & counts with.
+pub fn serialize_varuint32(value: u32) -> Bytes {
+    let mut bytes = vec![];
+    let mut remaining = value;
+    loop {
+        let byte = (remaining & 0x7f) as Byte;
+        remaining >>= 7;
+        match remaining {
+            0 => {
+                bytes.push(byte);
+                return bytes;
+            }
+            _ => bytes.push(byte | 0x80),
+        }
+    }
+}
+
+/// Deserializes the `varuint32` at the start of the bytes, returning it & its length in bytes.
+pub fn deserialize_varuint32(bytes: &[Byte]) -> Result<(u32, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().take(MAX_VARUINT32_NUM_BYTES).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return match u32::try_from(value) {
+                Ok(value) => Ok((value, i + 1)),
+                Err(_) => break,
+            };
+        }
+    }
+    Err(AppError::Custom(format!(
+        "✘ Invalid varuint32: 0x{}",
+        hex::encode(&bytes[..bytes.len().min(MAX_VARUINT32_NUM_BYTES)])
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eos_chain::{NumBytes, UnsignedInt, Write};
+
+    const SAMPLE_VALUES: [u32; 7] = [0, 1, 127, 128, 16_383, 16_384, u32::MAX];
+
+    #[test]
+    fn should_serialize_varuint32s_as_eos_chain_does() {
+        SAMPLE_VALUES.iter().for_each(|value| {
+            let unsigned_int = UnsignedInt::from(*value);
+            let mut expected_result = [0u8; MAX_VARUINT32_NUM_BYTES];
+            unsigned_int.write(&mut expected_result, &mut 0).unwrap();
+            assert_eq!(
+                serialize_varuint32(*value),
+                expected_result[..unsigned_int.num_bytes()].to_vec()
+            );
+        });
+    }
+
+    #[test]
+    fn should_deserialize_serialized_varuint32s() {
+        SAMPLE_VALUES.iter().for_each(|value| {
+            let bytes = [serialize_varuint32(*value), vec![0xff]].concat();
+            assert_eq!(
+                deserialize_varuint32(&bytes).unwrap(),
+                (*value, bytes.len() - 1)
+            );
+        });
+    }
+
+    #[test]
+    fn should_fail_to_deserialize_truncated_or_overlong_varuint32s() {
+        assert!(deserialize_varuint32(&[]).is_err());
+        assert!(deserialize_varuint32(&[0x80, 0x80]).is_err());
+        assert!(deserialize_varuint32(&[0xff, 0xff, 0xff, 0xff, 0x1f]).is_err());
+        assert!(deserialize_varuint32(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    eos_merkle_utils::{split_merkle_proof, verify_merkle_branch},
+    error::AppError,
+    get_action_digest::get_action_digest,
+    get_block_id::{
+        get_action_mroot_from_serialized_block_header, get_block_id_from_serialized_block_header,
+    },
+    hex_utils::strip_hex_prefix,
+    parse_eos_action::{parse_action_return_value_json, parse_eos_action_json},
+    parse_eos_action_receipts::parse_eos_action_receipt_json,
+    types::{Byte, Bytes, DigestScheme, MerkleProof, Output, Result},
+};
+use eos_chain::{Action as EosAction, Digest, SerializeData};
+
+fn get_verification_err(step: &str, msg: &str) -> AppError {
+    AppError::Custom(format!(
+        "✘ Error verifying proof at step: `{}`\n✘ {}",
+        step, msg
+    ))
+}
+
+fn check_bytes_match(step: &str, a: &[u8], b: &[u8], msg: &str) -> Result<()> {
+    match a == b {
+        true => {
+            info!("✔ Verification step `{}` passed!", step);
+            Ok(())
+        }
+        false => Err(get_verification_err(step, msg)),
+    }
+}
+
+pub fn parse_output_json_string(output_json_string: &str) -> Result<Output> {
+    match serde_json::from_str(output_json_string) {
+        Ok(result) => Ok(result),
+        Err(e) => Err(AppError::Custom(e.to_string())),
+    }
+}
+
+fn verify_serialized_action(output: &Output) -> Result<EosAction> {
+    let action = parse_eos_action_json(&output.action_json)?;
+    check_bytes_match(
+        "serialized action",
+        &action.to_serialize_data()?,
+        &hex::decode(strip_hex_prefix(&output.serialized_action))?,
+        "`serialized_action` does NOT match the `action_json`!",
+    )?;
+    Ok(action)
+}
+
+fn verify_action_digest(output: &Output, action: &EosAction) -> Result<()> {
+    let action_digest = hex::decode(strip_hex_prefix(&output.action_digest))?;
+    let action_return_value = parse_action_return_value_json(&output.action_json)?;
+    // NOTE: Outputs from before the digest scheme was recorded may have been made with either.
+    let digest_schemes = match output.digest_scheme {
+        Some(digest_scheme) => vec![digest_scheme],
+        None => vec![DigestScheme::Legacy, DigestScheme::ReturnValue],
+    };
+    let recomputed_digests = digest_schemes
+        .into_iter()
+        .map(|digest_scheme| get_action_digest(action, digest_scheme, &action_return_value))
+        .collect::<Result<Vec<Bytes>>>()?;
+    match recomputed_digests.contains(&action_digest) {
+        true => check_bytes_match(
+            "action digest",
+            &action_digest,
+            &hex::decode(strip_hex_prefix(&output.action_receipt_json.act_digest))?,
+            "`action_digest` does NOT match the `act_digest` in the action receipt!",
+        ),
+        false => Err(get_verification_err(
+            "action digest",
+            "Digest of the `serialized_action` does NOT match the `action_digest`!",
+        )),
+    }
+}
+
+fn verify_serialized_action_receipt(output: &Output) -> Result<Bytes> {
+    let action_receipt = parse_eos_action_receipt_json(&output.action_receipt_json)?;
+    check_bytes_match(
+        "serialized action receipt",
+        &action_receipt.to_serialize_data()?,
+        &hex::decode(strip_hex_prefix(&output.serialized_action_receipt))?,
+        "`serialized_action_receipt` does NOT match the `action_receipt_json`!",
+    )?;
+    Ok(action_receipt.digest()?.as_bytes().to_vec())
+}
+
+fn verify_action_receipt_digest(output: &Output, action_receipt_digest: &Bytes) -> Result<()> {
+    check_bytes_match(
+        "action receipt digest",
+        action_receipt_digest,
+        &hex::decode(strip_hex_prefix(&output.action_receipt_digest))?,
+        "Digest of the `serialized_action_receipt` does NOT match the `action_receipt_digest`!",
+    )
+    .and_then(|_| match output.action_proof.first() {
+        Some(leaf) => check_bytes_match(
+            "action receipt digest",
+            action_receipt_digest,
+            &hex::decode(strip_hex_prefix(leaf))?,
+            "`action_receipt_digest` is NOT the first element of the `action_proof`!",
+        ),
+        None => Err(get_verification_err(
+            "action receipt digest",
+            "`action_proof` is empty!",
+        )),
+    })
+}
+
+fn verify_action_proof(output: &Output, trusted_action_mroot: &[Byte]) -> Result<()> {
+    let (leaf, branch, root) = split_merkle_proof(&output.action_proof)
+        .map_err(|e| get_verification_err("action proof", &e.to_string()))?;
+    check_bytes_match(
+        "action proof",
+        trusted_action_mroot,
+        &root,
+        "Root of the `action_proof` does NOT match the trusted `action_mroot`!",
+    )
+    .and_then(|_| match verify_merkle_branch(&leaf, &branch, &root) {
+        true => Ok(()),
+        false => Err(get_verification_err(
+            "action proof",
+            "Branch of the `action_proof` does NOT hash up to the trusted `action_mroot`!",
+        )),
+    })
+}
+
+fn verify_block_proof_branch(block_proof: &MerkleProof, trusted_blockroot: &[Byte]) -> Result<()> {
+    let (leaf, branch, root) = split_merkle_proof(block_proof)
+        .map_err(|e| get_verification_err("block proof", &e.to_string()))?;
+    check_bytes_match(
+        "block proof",
+        trusted_blockroot,
+        &root,
+        "Root of the `block_proof` does NOT match the trusted `blockroot`!",
+    )
+    .and_then(|_| match verify_merkle_branch(&leaf, &branch, &root) {
+        true => Ok(()),
+        false => Err(get_verification_err(
+            "block proof",
+            "Branch of the `block_proof` does NOT hash up to the trusted `blockroot`!",
+        )),
+    })
+}
+
+/// Verifies the output's `block_proof` against a trusted `blockroot`, then its serialized header
+/// against its `block_id`, returning the `action_mroot` thereby proven.
+pub fn verify_block_proof(output: &Output, trusted_blockroot: &[Byte]) -> Result<Bytes> {
+    let (block_proof, serialized_block_header) =
+        match (&output.block_proof, &output.serialized_block_header) {
+            (Some(block_proof), Some(serialized_block_header)) => (
+                block_proof,
+                hex::decode(strip_hex_prefix(serialized_block_header))?,
+            ),
+            _ => {
+                return Err(get_verification_err(
+                    "block proof",
+                    "Output has no `block_proof` & `serialized_block_header`!",
+                ))
+            }
+        };
+    let block_id = hex::decode(strip_hex_prefix(&output.block_id))?;
+    match block_proof.first() {
+        Some(leaf) => check_bytes_match(
+            "block proof",
+            &block_id,
+            &hex::decode(strip_hex_prefix(leaf))?,
+            "`block_id` is NOT the first element of the `block_proof`!",
+        ),
+        None => Err(get_verification_err(
+            "block proof",
+            "`block_proof` is empty!",
+        )),
+    }
+    .and_then(|_| verify_block_proof_branch(block_proof, trusted_blockroot))
+    .and_then(|_| {
+        check_bytes_match(
+            "block header",
+            &block_id,
+            &get_block_id_from_serialized_block_header(&serialized_block_header)?,
+            "Id of the `serialized_block_header` does NOT match the `block_id`!",
+        )
+    })
+    .and_then(|_| get_action_mroot_from_serialized_block_header(&serialized_block_header))
+}
+
+pub fn verify_output(output: &Output, trusted_action_mroot: &[Byte]) -> Result<()> {
+    verify_serialized_action(output)
+        .and_then(|action| verify_action_digest(output, &action))
+        .and_then(|_| verify_serialized_action_receipt(output))
+        .and_then(|digest| verify_action_receipt_digest(output, &digest))
+        .and_then(|_| verify_action_proof(output, trusted_action_mroot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        proof_maker::ProofMaker,
+        test_utils::{
+            get_sample_eos_block_n, get_sample_later_block_ids_n, get_sample_output_n,
+            get_sample_signed_submission_json_n,
+        },
+    };
+
+    fn get_sample_action_mroot() -> Bytes {
+        get_sample_eos_block_n(1).unwrap().action_mroot.to_vec()
+    }
+
+    #[test]
+    fn should_verify_valid_output() {
+        let output = get_sample_output_n(1).unwrap();
+        if let Err(e) = verify_output(&output, &get_sample_action_mroot()) {
+            panic!("Should not error verifying valid output: {}", e);
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_against_wrong_action_mroot() {
+        let output = get_sample_output_n(1).unwrap();
+        let wrong_action_mroot = get_sample_eos_block_n(1)
+            .unwrap()
+            .transaction_mroot
+            .to_vec();
+        match verify_output(&output, &wrong_action_mroot) {
+            Ok(_) => panic!("Should not verify output against wrong action mroot!"),
+            Err(e) => assert!(e.to_string().contains("`action proof`")),
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_with_tampered_action() {
+        let mut output = get_sample_output_n(1).unwrap();
+        output.action_json.name = "transfer".to_string();
+        match verify_output(&output, &get_sample_action_mroot()) {
+            Ok(_) => panic!("Should not verify output with tampered action!"),
+            Err(e) => assert!(e.to_string().contains("`serialized action`")),
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_with_wrong_digest_scheme() {
+        let mut output = get_sample_output_n(1).unwrap();
+        assert_eq!(output.digest_scheme, Some(DigestScheme::Legacy));
+        output.digest_scheme = Some(DigestScheme::ReturnValue);
+        match verify_output(&output, &get_sample_action_mroot()) {
+            Ok(_) => panic!("Should not verify output with wrong digest scheme!"),
+            Err(e) => assert!(e.to_string().contains("`action digest`")),
+        }
+    }
+
+    #[test]
+    fn should_verify_output_without_digest_scheme() {
+        let mut output = get_sample_output_n(1).unwrap();
+        output.digest_scheme = None;
+        assert!(verify_output(&output, &get_sample_action_mroot()).is_ok());
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_with_tampered_action_receipt() {
+        let mut output = get_sample_output_n(1).unwrap();
+        output.action_receipt_json.recv_sequence += 1;
+        match verify_output(&output, &get_sample_action_mroot()) {
+            Ok(_) => panic!("Should not verify output with tampered action receipt!"),
+            Err(e) => assert!(e.to_string().contains("`serialized action receipt`")),
+        }
+    }
+
+    fn get_sample_output_with_block_proof() -> (Output, Bytes) {
+        let mut input_json = get_sample_signed_submission_json_n(1).unwrap();
+        input_json.later_block_ids = Some(get_sample_later_block_ids_n(1).unwrap());
+        let output = ProofMaker::generate(&input_json).unwrap();
+        let block_proof = output.block_proof.clone().unwrap();
+        let trusted_blockroot = hex::decode(&block_proof[block_proof.len() - 1]).unwrap();
+        (output, trusted_blockroot)
+    }
+
+    #[test]
+    fn should_fail_to_verify_output_with_empty_action_proof_node() {
+        let mut output = get_sample_output_n(1).unwrap();
+        output.action_proof[1] = "".to_string();
+        match verify_output(&output, &get_sample_action_mroot()) {
+            Ok(_) => panic!("Should not verify output with an empty action proof node!"),
+            Err(e) => assert!(e.to_string().contains("`action proof`")),
+        }
+    }
+
+    #[test]
+    fn should_verify_block_proof_against_trusted_blockroot() {
+        let (output, trusted_blockroot) = get_sample_output_with_block_proof();
+        let action_mroot = verify_block_proof(&output, &trusted_blockroot).unwrap();
+        assert_eq!(action_mroot, get_sample_action_mroot());
+        assert!(verify_output(&output, &action_mroot).is_ok());
+    }
+
+    #[test]
+    fn should_fail_to_verify_block_proof_with_empty_node() {
+        let (mut output, trusted_blockroot) = get_sample_output_with_block_proof();
+        output.block_proof.as_mut().unwrap()[1] = "".to_string();
+        match verify_block_proof(&output, &trusted_blockroot) {
+            Ok(_) => panic!("Should not verify block proof with an empty node!"),
+            Err(e) => assert!(e.to_string().contains("`block proof`")),
+        }
+        match ProofMaker::verify_against_blockroot(&output, &trusted_blockroot) {
+            Ok(_) => panic!("Should not verify output with an empty block proof node!"),
+            Err(e) => assert!(e.to_string().contains("`block proof`")),
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_block_proof_with_tampered_header_or_wrong_blockroot() {
+        let (output, trusted_blockroot) = get_sample_output_with_block_proof();
+        let err = verify_block_proof(&output, &[0; 32])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`block proof`"));
+        let mut tampered_output = output.clone();
+        let mut serialized_block_header =
+            hex::decode(tampered_output.serialized_block_header.unwrap()).unwrap();
+        serialized_block_header[80] ^= 1;
+        tampered_output.serialized_block_header = Some(hex::encode(serialized_block_header));
+        let err = verify_block_proof(&tampered_output, &trusted_blockroot)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`block header`"));
+        let err = verify_block_proof(&get_sample_output_n(1).unwrap(), &trusted_blockroot)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("has no `block_proof`"));
+    }
+}
@@ -1,35 +1,68 @@
 use crate::{
-    eos_merkle_utils::verify_merkle_proof,
+    eos_merkle_utils::{verify_merkle_proof, MerkleLeaf, MerkleRoot},
     error::AppError,
+    hex_utils::decode_checksum256,
     state::State,
     types::{MerkleProof, Result},
 };
 
-fn verify_proof(merkle_proof: &MerkleProof) -> Result<()> {
-    match verify_merkle_proof(merkle_proof) {
-        Ok(true) => Ok(()),
-        _ => Err(AppError::Custom(
+fn verify_proof(
+    merkle_proof: &MerkleProof,
+    leaf: &MerkleLeaf,
+    action_mroot: &MerkleRoot,
+) -> Result<()> {
+    let proof_leaf =
+        decode_checksum256(merkle_proof.first().map(String::as_str).unwrap_or_default())?;
+    match &proof_leaf == leaf && verify_merkle_proof(merkle_proof, action_mroot)? {
+        true => Ok(()),
+        false => Err(AppError::Custom(
             "✘ Error verifying generated merkle proof!".to_string(),
         )),
     }
 }
 
-pub fn verify_proof_in_state(state: State) -> Result<State> {
+pub fn verify_proofs_in_state(state: State) -> Result<State> {
+    let leaves = &state.get_merkle_tree()?[0];
+    let action_mroot = state.get_eos_block()?.action_mroot;
     state
-        .get_merkle_proof()
-        .and_then(verify_proof)
+        .get_merkle_proofs()?
+        .iter()
+        .zip(state.get_proof_indices()?.iter())
+        .try_for_each(|(proof, index)| verify_proof(proof, &leaves[*index as usize], &action_mroot))
         .and(Ok(state))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::get_sample_merkle_proof_n;
+    use crate::{
+        eos_merkle_utils::{get_merkle_root_from_branch, split_merkle_proof},
+        hex_utils::convert_bytes_to_checksum256,
+        test_utils::{
+            get_sample_action_receipts_n, get_sample_eos_block_n, get_sample_merkle_proof_n,
+            MERKLE_PROOF_INDEX,
+        },
+    };
+    use eos_chain::Digest;
+
+    fn get_sample_leaf() -> MerkleLeaf {
+        convert_bytes_to_checksum256(
+            get_sample_action_receipts_n(1).unwrap()[MERKLE_PROOF_INDEX as usize]
+                .digest()
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    fn get_sample_action_mroot() -> MerkleRoot {
+        get_sample_eos_block_n(1).unwrap().action_mroot
+    }
 
     #[test]
     fn should_verify_valid_merkle_proof() {
         let proof = get_sample_merkle_proof_n(1).unwrap();
-        if let Err(e) = verify_proof(&proof) {
+        if let Err(e) = verify_proof(&proof, &get_sample_leaf(), &get_sample_action_mroot()) {
             panic!("Should not error verifying valid proof {}", e);
         }
     }
@@ -38,8 +71,77 @@ mod tests {
     fn should_fail_to_verify_invalid_merkle_proof() {
         let mut proof = get_sample_merkle_proof_n(1).unwrap();
         proof.remove(1);
-        if let Ok(_) = verify_proof(&proof) {
+        if verify_proof(&proof, &get_sample_leaf(), &get_sample_action_mroot()).is_ok() {
             panic!("Should error verifying invalid proof!");
         }
     }
+
+    #[test]
+    fn should_fail_to_verify_self_consistent_proof_against_external_root() {
+        let proof = get_sample_merkle_proof_n(1).unwrap();
+        let (_, mut branch, _) = split_merkle_proof(&proof).unwrap();
+        branch.remove(0);
+        let leaf = get_sample_leaf();
+        let tampered_root = get_merkle_root_from_branch(&leaf, &branch);
+        let mut tampered_proof = vec![hex::encode(leaf)];
+        tampered_proof.extend(branch.iter().map(hex::encode));
+        tampered_proof.push(hex::encode(tampered_root));
+        assert!(verify_merkle_proof(&tampered_proof, &tampered_root).unwrap());
+        if verify_proof(&tampered_proof, &leaf, &get_sample_action_mroot()).is_ok() {
+            panic!("Should error verifying self-consistent proof against external root!");
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_proof_for_wrong_leaf() {
+        let proof = get_sample_merkle_proof_n(1).unwrap();
+        let wrong_leaf = convert_bytes_to_checksum256(
+            get_sample_action_receipts_n(1).unwrap()[0]
+                .digest()
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+        if verify_proof(&proof, &wrong_leaf, &get_sample_action_mroot()).is_ok() {
+            panic!("Should error verifying proof for wrong leaf!");
+        }
+    }
+
+    #[test]
+    fn should_fail_to_verify_proof_with_empty_or_short_sibling() {
+        let proof = get_sample_merkle_proof_n(1).unwrap();
+        let mut empty_sibling_proof = proof.clone();
+        empty_sibling_proof[1] = "".to_string();
+        assert!(split_merkle_proof(&empty_sibling_proof).is_err());
+        assert!(verify_merkle_proof(&empty_sibling_proof, &get_sample_action_mroot()).is_err());
+        let mut short_sibling_proof = proof;
+        short_sibling_proof[1].truncate(62);
+        assert!(verify_proof(
+            &short_sibling_proof,
+            &get_sample_leaf(),
+            &get_sample_action_mroot()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn no_std_verifier_should_agree_with_sample_merkle_proof() {
+        let proof_bytes = get_sample_merkle_proof_n(1)
+            .unwrap()
+            .iter()
+            .map(|node| hex::decode(node).unwrap())
+            .collect::<Vec<_>>()
+            .concat();
+        let proof = eos_merkle_verifier::parse_digests(&proof_bytes).unwrap();
+        let mut action_mroot = [0u8; eos_merkle_verifier::DIGEST_LENGTH];
+        action_mroot.copy_from_slice(&get_sample_action_mroot());
+        assert!(eos_merkle_verifier::verify_merkle_proof(
+            &proof,
+            &action_mroot
+        ));
+        assert!(!eos_merkle_verifier::verify_merkle_proof(
+            &proof[1..],
+            &action_mroot
+        ));
+    }
 }
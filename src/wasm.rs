@@ -0,0 +1,202 @@
+use crate::{
+    eos_merkle_utils::{generate_merkle_proof, get_merkle_root_from_tree, get_merkle_tree},
+    get_action_digest::get_action_digest,
+    hex_utils::strip_hex_prefix,
+    parse_eos_action::{parse_action_return_value_json, parse_eos_action_json},
+    parse_eos_action_receipts::parse_eos_action_receipt_json,
+    proof_maker::{GenerateOptions, ProofMaker},
+    types::{Bytes, DigestScheme, EosActionJson, EosActionReceiptJson, MerkleProof, Result},
+};
+use eos_chain::Digest;
+use wasm_bindgen::prelude::*;
+
+// NOTE: The `*_json_string` fxns below hold the logic so that they're testable off of wasm too,
+// whilst the `#[wasm_bindgen]` wrappers merely convert any error into a JS one.
+
+fn convert_err_to_js_value<T>(result: Result<T>) -> std::result::Result<T, JsValue> {
+    result.map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+fn parse_hex_strings_json(hex_strings_json_string: &str) -> Result<Vec<Bytes>> {
+    serde_json::from_str::<Vec<String>>(hex_strings_json_string)?
+        .iter()
+        .map(|hex_string| Ok(hex::decode(strip_hex_prefix(hex_string))?))
+        .collect()
+}
+
+fn get_action_digest_from_json_string(
+    action_json_string: &str,
+    digest_scheme_string: &str,
+) -> Result<String> {
+    let action_json: EosActionJson = serde_json::from_str(action_json_string)?;
+    let digest_scheme: DigestScheme =
+        serde_json::from_value(serde_json::Value::String(digest_scheme_string.to_string()))?;
+    Ok(hex::encode(get_action_digest(
+        &parse_eos_action_json(&action_json)?,
+        digest_scheme,
+        &parse_action_return_value_json(&action_json)?,
+    )?))
+}
+
+fn get_action_receipt_digest_from_json_string(action_receipt_json_string: &str) -> Result<String> {
+    let action_receipt_json: EosActionReceiptJson =
+        serde_json::from_str(action_receipt_json_string)?;
+    Ok(parse_eos_action_receipt_json(&action_receipt_json)?
+        .digest()?
+        .to_string())
+}
+
+fn get_merkle_root_from_leaves_json_string(leaves_json_string: &str) -> Result<String> {
+    parse_hex_strings_json(leaves_json_string)
+        .and_then(get_merkle_tree)
+        .map(|tree| hex::encode(get_merkle_root_from_tree(&tree)))
+}
+
+fn generate_merkle_proof_from_leaves_json_string(
+    index: usize,
+    leaves_json_string: &str,
+) -> Result<String> {
+    parse_hex_strings_json(leaves_json_string)
+        .and_then(get_merkle_tree)
+        .and_then(|tree| generate_merkle_proof(index, &tree))
+        .and_then(|proof| Ok(serde_json::to_string(&proof)?))
+}
+
+fn verify_merkle_proof_json_string(proof_json_string: &str, root_hex: &str) -> Result<bool> {
+    let proof: MerkleProof = serde_json::from_str(proof_json_string)?;
+    crate::eos_merkle_utils::verify_merkle_proof(&proof, &hex::decode(strip_hex_prefix(root_hex))?)
+}
+
+/// Takes & returns the same JSON as the CLI's `generate` command.
+#[wasm_bindgen]
+pub fn generate(
+    input_json: &str,
+    all: bool,
+    multi_proof: bool,
+) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(ProofMaker::generate_json_string(
+        input_json,
+        GenerateOptions { all, multi_proof },
+    ))
+}
+
+/// Takes & returns the same JSON as the CLI's `verify` command.
+#[wasm_bindgen]
+pub fn verify(output_json: &str, action_mroot_hex: &str) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(
+        hex::decode(strip_hex_prefix(action_mroot_hex))
+            .map_err(Into::into)
+            .and_then(|action_mroot| ProofMaker::verify_json_string(output_json, &action_mroot)),
+    )
+}
+
+/// Returns the hex digest of an action JSON, via either the `legacy` or `return_value` scheme.
+#[wasm_bindgen]
+pub fn action_digest(
+    action_json: &str,
+    digest_scheme: &str,
+) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(get_action_digest_from_json_string(
+        action_json,
+        digest_scheme,
+    ))
+}
+
+/// Returns the hex digest of an action receipt JSON, ie a leaf of the `action_mroot` tree.
+#[wasm_bindgen]
+pub fn action_receipt_digest(action_receipt_json: &str) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(get_action_receipt_digest_from_json_string(
+        action_receipt_json,
+    ))
+}
+
+/// Returns the hex merkle root of a JSON array of hex leaves.
+#[wasm_bindgen]
+pub fn merkle_root(leaves_json: &str) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(get_merkle_root_from_leaves_json_string(leaves_json))
+}
+
+/// Returns the JSON merkle proof for the leaf at `index` of a JSON array of hex leaves.
+#[wasm_bindgen]
+pub fn merkle_proof(index: usize, leaves_json: &str) -> std::result::Result<String, JsValue> {
+    convert_err_to_js_value(generate_merkle_proof_from_leaves_json_string(
+        index,
+        leaves_json,
+    ))
+}
+
+/// Verifies a JSON merkle proof against a hex merkle root.
+#[wasm_bindgen]
+pub fn verify_merkle_proof(proof_json: &str, root_hex: &str) -> std::result::Result<bool, JsValue> {
+    convert_err_to_js_value(verify_merkle_proof_json_string(proof_json, root_hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_eos_action_receipts::sort_action_receipt_jsons_by_global_sequence,
+        test_utils::{
+            get_sample_eos_block_n, get_sample_merkle_proof_n, get_sample_submission_json_n,
+        },
+    };
+
+    fn get_sample_leaves_json_string_n(n: usize) -> String {
+        let leaves = sort_action_receipt_jsons_by_global_sequence(
+            &get_sample_submission_json_n(n).unwrap().action_receipts,
+        )
+        .iter()
+        .map(|receipt_json| {
+            get_action_receipt_digest_from_json_string(
+                &serde_json::to_string(receipt_json).unwrap(),
+            )
+        })
+        .collect::<Result<Vec<String>>>()
+        .unwrap();
+        serde_json::to_string(&leaves).unwrap()
+    }
+
+    #[test]
+    fn should_get_action_digest_from_json_string() {
+        let action_json = get_sample_submission_json_n(1).unwrap().action.unwrap();
+        let result = get_action_digest_from_json_string(
+            &serde_json::to_string(&action_json).unwrap(),
+            "legacy",
+        )
+        .unwrap();
+        let expected_result = "364afa1cc13bca5dce1027f089e56889171373f66f5e3e59637251aaaeac4caa";
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn should_fail_to_get_action_digest_with_unknown_digest_scheme() {
+        let action_json = get_sample_submission_json_n(1).unwrap().action.unwrap();
+        let result = get_action_digest_from_json_string(
+            &serde_json::to_string(&action_json).unwrap(),
+            "unknown",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_get_merkle_root_from_leaves_json_string() {
+        let result =
+            get_merkle_root_from_leaves_json_string(&get_sample_leaves_json_string_n(1)).unwrap();
+        assert_eq!(
+            result,
+            hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot)
+        );
+    }
+
+    #[test]
+    fn should_generate_and_verify_merkle_proof_from_json_strings() {
+        let proof_json_string =
+            generate_merkle_proof_from_leaves_json_string(3, &get_sample_leaves_json_string_n(1))
+                .unwrap();
+        let proof: MerkleProof = serde_json::from_str(&proof_json_string).unwrap();
+        assert_eq!(proof, get_sample_merkle_proof_n(1).unwrap());
+        let action_mroot = hex::encode(get_sample_eos_block_n(1).unwrap().action_mroot);
+        assert!(verify_merkle_proof_json_string(&proof_json_string, &action_mroot).unwrap());
+        assert!(!verify_merkle_proof_json_string(&proof_json_string, &"00".repeat(32)).unwrap());
+    }
+}